@@ -0,0 +1,264 @@
+use std::fmt::Write;
+
+use serde::{Serialize, Deserialize};
+
+use crate::catalogue::{Catalogue, Purchases};
+use crate::objective::Outcome;
+use crate::sim::{Simulation, STEPS_PER_TURN};
+use crate::tiles::Tiles;
+
+/// A tile to buy and place at the start of a turn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Placement {
+    pub turn: usize,
+    pub tile: Tiles,
+    pub x: usize,
+    pub y: usize,
+}
+
+/// How the simulated player decides what to place.
+#[derive(Clone, Debug)]
+pub enum Policy {
+    /// never place anything
+    Idle,
+    /// each turn, `chance` percent of the time, buy a random affordable
+    /// tile and place it on a random cell it can go on
+    Random { chance: u32 },
+    /// the same placements every game, skipped if they can't be afforded
+    Script(Vec<Placement>),
+}
+
+/// Settings shared by every game of a batch.
+#[derive(Clone, Debug)]
+pub struct BatchConfig {
+    pub games: usize,
+    /// the first game's seed, each game after uses the next one
+    pub seed: u64,
+    /// money at the start of the level
+    pub budget: usize,
+    /// what can be bought, with its prices and stock
+    pub shop: Catalogue,
+    /// a game that hasn't ended after this many turns is unfinished
+    pub max_turns: usize,
+    pub steps_per_turn: usize,
+    pub policy: Policy,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        BatchConfig {
+            games: 1000,
+            seed: 0,
+            budget: 10,
+            shop: Catalogue::default(),
+            max_turns: 40,
+            steps_per_turn: STEPS_PER_TURN,
+            policy: Policy::Random { chance: 50 },
+        }
+    }
+}
+
+/// How one game went.
+#[derive(Clone, Debug, Serialize)]
+pub struct GameResult {
+    pub game: usize,
+    pub seed: u64,
+    /// `Playing` if the game hit the turn limit
+    pub outcome: Outcome,
+    pub turns: usize,
+    pub harvested: usize,
+    /// tiles bought, not counting the key
+    pub bought: usize,
+    pub spent: usize,
+    /// money at the end of each turn, starting with the budget
+    pub money: Vec<usize>,
+}
+
+/// Totals over every game of a batch.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Summary {
+    pub games: usize,
+    pub won: usize,
+    pub lost: usize,
+    pub unfinished: usize,
+    pub mean_turns: f64,
+    pub mean_harvested: f64,
+}
+
+impl Summary {
+    pub fn new(results: &[GameResult]) -> Summary {
+        let games = results.len();
+        let count = |o| results.iter().filter(|r| r.outcome == o).count();
+        let mean = |f: fn(&GameResult) -> usize| if games == 0 {
+            0.0
+        } else {
+            results.iter().map(f).sum::<usize>() as f64 / games as f64
+        };
+        Summary {
+            games,
+            won: count(Outcome::Won),
+            lost: count(Outcome::Lost),
+            unfinished: count(Outcome::Playing),
+            mean_turns: mean(|r| r.turns),
+            mean_harvested: mean(|r| r.harvested),
+        }
+    }
+}
+
+/// Play `config.games` games on the map loaded into `sim`.
+/// Every game starts from the state `sim` is in when this is called,
+/// and `sim` is put back in that state afterwards.
+pub fn run(sim: &mut Simulation, config: &BatchConfig) -> Vec<GameResult> {
+    sim.set_steps_per_turn(config.steps_per_turn);
+    let start = sim.save_state();
+    let mut results = Vec::with_capacity(config.games);
+    for game in 0..config.games {
+        let seed = config.seed.wrapping_add(game as u64);
+        if sim.restore(start.clone()).is_err() {
+            break;
+        }
+        results.push(play(sim, config, game, seed));
+    }
+    let _ = sim.restore(start);
+    results
+}
+
+fn play(sim: &mut Simulation, config: &BatchConfig, game: usize, seed: u64) -> GameResult {
+    let mut rng = Rng::new(seed);
+    let mut purchases = Purchases::new();
+    let mut result = GameResult {
+        game, seed,
+        outcome: Outcome::Playing,
+        turns: 0,
+        harvested: 0,
+        bought: 0,
+        spent: 0,
+        money: vec![config.budget],
+    };
+    let mut money = config.budget;
+    let mut key_used = false;
+    for turn in 0..config.max_turns {
+        purchases.set_turn(turn);
+        for (tile, x, y) in placements(sim, config, turn, money, &purchases, &mut rng) {
+            let cost = match config.shop.affordable(tile, money, &purchases) {
+                Some(c) => c,
+                None => continue,
+            };
+            if let Ok(true) = sim.place_tile(tile, x, y) {
+                money -= cost;
+                purchases.buy(tile);
+                result.bought += 1;
+                result.spent += cost;
+            }
+        }
+        // like a player would, put the key in the door as soon as it can be bought
+        if let Some(cost) = config.shop.affordable(Tiles::Key, money, &purchases).filter(|_| !key_used) {
+            if let Some(door) = sim.board.map.iter().position(|t| *t == Tiles::Door) {
+                let (x, y) = (door % sim.board.w, door / sim.board.w);
+                if sim.place_tile(Tiles::Key, x, y).is_ok() {
+                    money -= cost;
+                    purchases.buy(Tiles::Key);
+                    result.spent += cost;
+                    key_used = true;
+                }
+            }
+        }
+        result.outcome = sim.check(money);
+        if result.outcome != Outcome::Playing {
+            break;
+        }
+        let harvested = sim.advance_turn().harvested;
+        money += harvested;
+        result.harvested += harvested;
+        result.money.push(money);
+        result.outcome = sim.check(money);
+        if result.outcome != Outcome::Playing {
+            break;
+        }
+    }
+    result.turns = sim.turn_count();
+    result
+}
+
+/// What the policy wants to place this turn.
+fn placements(sim: &Simulation, config: &BatchConfig, turn: usize, money: usize,
+              purchases: &Purchases, rng: &mut Rng) -> Vec<(Tiles, usize, usize)> {
+    match &config.policy {
+        Policy::Idle => Vec::new(),
+        Policy::Script(script) => script.iter()
+            .filter(|p| p.turn == turn)
+            .map(|p| (p.tile, p.x, p.y))
+            .collect(),
+        Policy::Random { chance } => {
+            if rng.below(100) as u32 >= *chance {
+                return Vec::new();
+            }
+            let affordable: Vec<Tiles> = config.shop.items.iter()
+                .map(|i| i.tile)
+                .filter(|t| *t != Tiles::Key && config.shop.affordable(*t, money, purchases).is_some())
+                .collect();
+            if affordable.is_empty() {
+                return Vec::new();
+            }
+            let tile = affordable[rng.below(affordable.len())];
+            let cells: Vec<usize> = (0..sim.board.map.len())
+                .filter(|i| {
+                    let t = sim.board.map[*i];
+                    t != Tiles::None && t != Tiles::Door && t != tile
+                })
+                .collect();
+            if cells.is_empty() {
+                return Vec::new();
+            }
+            let i = cells[rng.below(cells.len())];
+            vec![(tile, i % sim.board.w, i / sim.board.w)]
+        },
+    }
+}
+
+/// One row per game, the money curve is a `;` separated list in the last column.
+pub fn to_csv(results: &[GameResult]) -> String {
+    let mut csv = String::from("game,seed,outcome,turns,harvested,bought,spent,money\n");
+    for r in results.iter() {
+        let money: Vec<String> = r.money.iter().map(|m| m.to_string()).collect();
+        let _ = writeln!(csv, "{},{},{:?},{},{},{},{},{}",
+                         r.game, r.seed, r.outcome, r.turns, r.harvested,
+                         r.bought, r.spent, money.join(";"));
+    }
+    csv
+}
+
+pub fn to_json(results: &[GameResult]) -> Result<String, serde_json::Error> {
+    #[derive(Serialize)]
+    struct Batch<'a> {
+        summary: Summary,
+        games: &'a [GameResult],
+    }
+    serde_json::to_string_pretty(&Batch { summary: Summary::new(results), games: results })
+}
+
+/// A small seeded generator so a batch can be run again with the same games.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        // splitmix64 so that neighbouring seeds give unrelated games
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        Rng((z ^ (z >> 31)) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        // xorshift64*
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// a number in `0..n`, `n` must not be 0
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
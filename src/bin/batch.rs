@@ -0,0 +1,108 @@
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use bunny_patch::batch::{self, BatchConfig, Placement, Policy, Summary};
+use bunny_patch::catalogue::Catalogue;
+use bunny_patch::sim::Simulation;
+use bunny_patch::tiles::{Rules, Tiles};
+
+const USAGE: &str = "usage: batch <map.tmx> [--games <n>] [--seed <n>] \
+[--policy idle | random | <script.ron>] [--chance <percent>] [--budget <money>] \
+[--turns <max turns>] [--steps <steps per turn>] [--price <Tile>=<cost>]... \
+[--rules <rules.ron>] [--shop <shop.ron>] [--format csv | json] [--out <file>]";
+
+/// Play many games of a map without a window and write how each went,
+/// for tuning turn length, shop prices and tile rules.
+/// Prices, pricing and stock come from the level's shop, `--price` changes a starting price.
+/// A script is a ron list of `(turn: 0, tile: Goat, x: 1, y: 2)` placements.
+pub fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run() -> Result<(), String> {
+    let args: Vec<String> = std::env::args().collect();
+    let map_path = match args.get(1) {
+        Some(p) if !p.starts_with("--") => PathBuf::from(p),
+        _ => return Err(String::from(USAGE)),
+    };
+    let arg = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1));
+    let num = |name: &str| -> Result<Option<usize>, String> {
+        arg(name).map(|v| v.parse::<usize>()
+                      .map_err(|e| format!("bad value for {}: {}", name, e)))
+            .transpose()
+    };
+
+    let mut config = BatchConfig::default();
+    for (name, value) in [("--games", &mut config.games),
+                          ("--budget", &mut config.budget),
+                          ("--turns", &mut config.max_turns),
+                          ("--steps", &mut config.steps_per_turn)] {
+        if let Some(n) = num(name)? {
+            *value = n;
+        }
+    }
+    if let Some(n) = num("--seed")? {
+        config.seed = n as u64;
+    }
+    let chance = num("--chance")?.unwrap_or(50).min(100) as u32;
+    config.policy = match arg("--policy").map(|p| p.as_str()) {
+        None | Some("random") => Policy::Random { chance },
+        Some("idle") => Policy::Idle,
+        Some(script) => {
+            let text = std::fs::read_to_string(script)
+                .map_err(|e| format!("{}: {}", script, e))?;
+            let script: Vec<Placement> = ron::from_str(&text)
+                .map_err(|e| format!("could not parse script: {}", e))?;
+            Policy::Script(script)
+        },
+    };
+    let shop = match arg("--shop") {
+        Some(path) => Catalogue::load(Path::new(path)).map_err(|e| e.to_string())?,
+        None => Catalogue::default(),
+    };
+    config.shop = shop.for_map_file(&map_path).map_err(|e| e.to_string())?;
+    for (i, a) in args.iter().enumerate() {
+        if a != "--price" {
+            continue;
+        }
+        let (tile, cost) = args.get(i + 1)
+            .and_then(|p| p.split_once('='))
+            .ok_or_else(|| String::from("--price expects <Tile>=<cost>"))?;
+        let tile: Tiles = ron::from_str(tile)
+            .map_err(|_| format!("unknown tile \"{}\"", tile))?;
+        let cost: usize = cost.parse()
+            .map_err(|e| format!("bad price for {:?}: {}", tile, e))?;
+        match config.shop.items.iter_mut().find(|i| i.tile == tile) {
+            Some(item) => item.cost = cost,
+            None => return Err(format!("{:?} isn't sold in the shop", tile)),
+        }
+    }
+
+    let mut sim = Simulation::new();
+    if let Some(rules) = arg("--rules") {
+        sim.set_rules(Rules::load(Path::new(rules)).map_err(|e| e.to_string())?);
+    }
+    sim.load_file(&map_path).map_err(|e| e.to_string())?;
+
+    let results = batch::run(&mut sim, &config);
+    let text = match arg("--format").map(|f| f.as_str()) {
+        None | Some("csv") => batch::to_csv(&results),
+        Some("json") => batch::to_json(&results).map_err(|e| e.to_string())?,
+        Some(f) => return Err(format!("unknown format \"{}\"", f)),
+    };
+    match arg("--out") {
+        Some(out) => std::fs::write(out, text).map_err(|e| format!("{}: {}", out, e))?,
+        None => print!("{}", text),
+    }
+
+    let s = Summary::new(&results);
+    eprintln!("{} games: {} won, {} lost, {} unfinished, {:.1} turns and {:.1} carrots on average",
+              s.games, s.won, s.lost, s.unfinished, s.mean_turns, s.mean_harvested);
+    Ok(())
+}
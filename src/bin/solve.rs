@@ -0,0 +1,104 @@
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use bunny_patch::catalogue::Catalogue;
+use bunny_patch::sim::Simulation;
+use bunny_patch::solver::{self, SolverConfig};
+use bunny_patch::tiles::Rules;
+
+const USAGE: &str = "usage: solve <map.tmx> [--budget <money>] [--turns <max turns>] \
+[--beam <width> | --bfs] [--nodes <max turns simulated>] [--rules <rules.ron>] [--shop <shop.ron>] [--out <replay.ron>]";
+
+/// Search a map for a winning run.
+/// Exits with 0 if the map can be beaten, 1 if no solution was found and 2 on bad
+/// input or if the replay could not be written.
+pub fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
+    let map_path = match args.get(1) {
+        Some(p) if !p.starts_with("--") => PathBuf::from(p),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let arg = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1));
+    let num = |name: &str| arg(name).map(|v| v.parse::<usize>());
+
+    let mut config = SolverConfig::default();
+    for (name, value) in [("--budget", &mut config.budget),
+                          ("--turns", &mut config.max_turns),
+                          ("--nodes", &mut config.max_nodes)] {
+        match num(name) {
+            Some(Ok(n)) => *value = n,
+            Some(Err(e)) => {
+                eprintln!("bad value for {}: {}", name, e);
+                return ExitCode::from(2);
+            },
+            None => (),
+        }
+    }
+    match num("--beam") {
+        Some(Ok(n)) => config.beam_width = Some(n),
+        Some(Err(e)) => {
+            eprintln!("bad value for --beam: {}", e);
+            return ExitCode::from(2);
+        },
+        None => (),
+    }
+    if args.iter().any(|a| a == "--bfs") {
+        config.beam_width = None;
+    }
+
+    let mut sim = Simulation::new();
+    if let Some(rules) = arg("--rules") {
+        match Rules::load(Path::new(rules)) {
+            Ok(r) => sim.set_rules(r),
+            Err(e) => {
+                eprintln!("{}", e);
+                return ExitCode::from(2);
+            }
+        }
+    }
+    if let Err(e) = sim.load_file(&map_path) {
+        eprintln!("{}", e);
+        return ExitCode::from(2);
+    }
+    let shop = match arg("--shop") {
+        Some(path) => Catalogue::load(Path::new(path)).map_err(|e| e.into()),
+        None => Ok(Catalogue::default()),
+    };
+    match shop.and_then(|s| s.for_map_file(&map_path)) {
+        Ok(s) => config.shop = s,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
+    }
+
+    let report = solver::solve(&mut sim, &config);
+    println!("searched {} turns", report.explored);
+    match report.solution {
+        Some(s) => {
+            println!("winnable in {} turns, harvesting {} carrots with {} money left over",
+                     s.turns, s.harvested, s.money);
+            for a in s.actions.iter() {
+                println!("  {:?}", a);
+            }
+            if let Some(out) = arg("--out") {
+                if let Err(e) = s.replay(map_path.clone(), config.budget).write(Path::new(out)) {
+                    eprintln!("{}", e);
+                    return ExitCode::from(2);
+                }
+            }
+            ExitCode::SUCCESS
+        },
+        None => {
+            if report.exhaustive {
+                println!("not winnable within {} turns", config.max_turns);
+            } else {
+                println!("no solution found, the search was cut short so the map may still be winnable");
+            }
+            ExitCode::from(1)
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use std::path::Path;
+use std::process::ExitCode;
+
+use bunny_patch::validate::{validate, Severity};
+
+const USAGE: &str = "usage: validate [--strict] <map.tmx>...";
+
+/// Check maps for problems before they ship.
+/// Exits with 0 if every map is fine, 1 if any map has errors, or warnings
+/// with `--strict`, and 2 if no maps were given.
+pub fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let strict = args.iter().any(|a| a == "--strict");
+    let maps: Vec<&String> = args.iter().filter(|a| !a.starts_with("--")).collect();
+    if maps.is_empty() {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    }
+
+    let mut failed = false;
+    for m in maps {
+        for d in validate(Path::new(m)) {
+            println!("{}: {}", m, d);
+            if d.severity == Severity::Error || strict {
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
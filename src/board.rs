@@ -1,17 +1,25 @@
 use crate::button::Button;
-use crate::tiles::{Tiles, Tilemap, Tile, Choice, TILE};
-use crate::ui::Ui;
+use crate::catalogue::Catalogue;
+use crate::error::GameError;
+use crate::history::{History, Snapshot};
+use crate::replay::{Action, Replay};
+use crate::save::SaveGame;
+use crate::shop::Shop;
+use crate::sim::Simulation;
+use crate::tiles::{Tiles, TileSprites, TILE, MapSnapshot, MapDiff, TileTypes};
+use crate::ui::{Ui, Tool};
 
 use sdl_helper::geometry::Vec2;
-use sdl_helper::GameObject;
+use sdl_helper::{GameObject, Colour};
 use sdl_helper::input::{Controls, keyboard::MouseButton, keyboard::Key};
 use sdl_helper::{map::Map, Error, Render, Camera};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub struct Board {
     map: Option<Map>,
-    obj_map: Vec<Box<dyn Tile>>,
-    pub board: Tilemap,
+    map_path: PathBuf,
+    pub sim: Simulation,
+    sprites: TileSprites,
     outline: GameObject,
     is_selected: bool,
     selected: (usize, usize),
@@ -19,14 +27,26 @@ pub struct Board {
     next_btn: Button,
     skip_btn: Button,
     pub dir_btns: [Button; 4],
+    undo_btns: [Button; 2],
     btn_clicked: bool,
-    turns_to_change: usize,
     turn_timer: f64,
-    pub complete: bool,
-    pub lose: bool,
+    history: History,
+    /// board at the start of the current turn
+    turn_start: Option<MapSnapshot>,
+    /// what has changed since `turn_start`
+    changed: MapDiff,
+    highlight: GameObject,
+    /// the level's shop, for what selling a tile gives back
+    catalogue: Catalogue,
+    /// the goat being given a patrol path
+    patrolling: Option<(usize, usize)>,
+    /// the patrol tool is in use
+    show_paths: bool,
+    /// the tile that would be placed, for previewing a structure's footprint
+    held: Tiles,
 }
 
-const STEPS_PER_TURN : usize = 6;
+/// seconds between each step of a turn being shown
 const TURN_LENGTH: f64 = 0.5;
 
 impl Board {
@@ -34,7 +54,8 @@ impl Board {
         let next_pos = Vec2::new(400.0, 295.0);
         Ok(
             Board {
-                board : Tilemap::new(
+                sim: Simulation::new(),
+                sprites: TileSprites::new(
                     render.texture_manager.load(Path::new(
                         "resources/textures/tiles/game_tiles.png"))
                         ?),
@@ -65,7 +86,7 @@ impl Board {
                     next_pos
                 ),
                 map: None,
-                obj_map: Vec::new(),
+                map_path: PathBuf::new(),
                 is_selected: false,
                 selected: (0, 0),
                 outline: GameObject::new_from_tex(render.texture_manager.load(
@@ -73,51 +94,139 @@ impl Board {
                 )?),
                 money: 0,
                 dir_btns: get_dir_btn(render)?,
+                undo_btns: get_undo_btn(render)?,
                 btn_clicked: false,
-                turns_to_change: 0,
                 turn_timer: 0.0,
-                complete: false,
-                lose: false,
+                history: History::new(),
+                turn_start: None,
+                changed: MapDiff::default(),
+                highlight: GameObject::new_from_tex(render.texture_manager.load(
+                    Path::new("resources/textures/tiles/blank.png")
+                )?),
+                catalogue: Catalogue::default(),
+                patrolling: None,
+                show_paths: false,
+                held: Tiles::None,
             }
         )
     }
 
-    pub fn load_map(&mut self, path: &Path, render: &mut Render) -> Result<(), Error> {
+    /// Load a map and start a fresh run on it,
+    /// the current board is kept if the map is malformed.
+    pub fn load_map(&mut self, path: &Path, render: &mut Render) -> Result<(), GameError> {
+        let types = TileTypes::load(path)?;
         let map = Map::new(path, &mut render.texture_manager,
                            Path::new("resources/fonts/"),
                            &mut render.font_manager
         )?;
-        self.board.set_map(&map.tiled_map);
-        self.board.set_tile_objs(&mut self.obj_map);
+        self.sim.load_map(&map.tiled_map, &types)?;
         self.map = Some(map);
+        self.map_path = path.to_path_buf();
+        self.patrolling = None;
+        self.history.clear();
+        self.turn_timer = 0.0;
+        self.clear_changes();
         Ok(())
     }
 
-    fn skip(&mut self, ui: &mut Ui) {
-        self.step(ui);
-        self.turns_to_change -= 1;
+    /// `base` changed by the properties of the loaded map, see `Catalogue::for_map`.
+    pub fn shop_catalogue(&self, base: &Catalogue) -> Result<Catalogue, GameError> {
+        match &self.map {
+            Some(map) => Ok(base.for_map(&map.tiled_map.props,
+                                         self.map_path.parent().unwrap_or(Path::new("")))?),
+            None => Ok(base.clone()),
+        }
     }
 
-    fn check_carrot(&mut self) {
-        if self.turns_to_change == 0 {
-            let mut carrot = false;
-            for t in self.board.map.iter() {
-                if *t == Tiles::Carrot {
-                    carrot = true;
-                }
-            }
-            if !carrot {
-                self.lose = true;
-            }
+    pub fn set_catalogue(&mut self, catalogue: Catalogue) {
+        self.catalogue = catalogue;
+    }
+
+    pub fn save_game(&self, ui: &mut Ui) -> SaveGame {
+        SaveGame::new(self.map_path.clone(), ui.get_money(), ui.inventory().clone(),
+                      self.sim.save_state(), ui.purchases().clone())
+    }
+
+    /// Restore a save, the save's map must already be loaded with `load_map`.
+    pub fn restore(&mut self, save: SaveGame, ui: &mut Ui) -> Result<(), GameError> {
+        self.sim.restore(save.sim)?;
+        self.patrolling = None;
+        self.turn_timer = 0.0;
+        self.history.clear();
+        self.clear_changes();
+        ui.set_money(save.money);
+        ui.set_inventory(save.inventory)?;
+        ui.set_purchases(save.purchases);
+        // a replay can only be played from the start of a map
+        ui.stop_recording();
+        Ok(())
+    }
+
+    /// Play back a replay, the replay's map must have just been loaded with `load_map`.
+    /// Purchases are checked against the prices in `shop`, which should be stocked for the map.
+    pub fn play_replay(&mut self, replay: &Replay, shop: &Shop, ui: &mut Ui) -> Result<(), GameError> {
+        let outcome = replay.play(&mut self.sim, shop.catalogue())?;
+        ui.set_money(outcome.money);
+        ui.set_inventory(outcome.inventory)?;
+        ui.set_purchases(outcome.purchases);
+        Ok(())
+    }
+
+    fn snapshot(&self, ui: &mut Ui) -> Snapshot {
+        Snapshot {
+            sim: self.sim.save_state(),
+            money: ui.get_money(),
+            inventory: ui.inventory().clone(),
+            purchases: ui.purchases().clone(),
+        }
+    }
+
+    fn load_snapshot(&mut self, s: Snapshot, ui: &mut Ui) -> Result<(), GameError> {
+        self.sim.restore(s.sim)?;
+        self.turn_timer = 0.0;
+        self.clear_changes();
+        self.patrolling = self.patrolling.filter(|(x, y)| self.sim.can_patrol(*x, *y));
+        ui.set_money(s.money);
+        ui.set_purchases(s.purchases);
+        ui.set_inventory(s.inventory)
+    }
+
+    fn clear_changes(&mut self) {
+        self.turn_start = None;
+        self.changed = MapDiff::default();
+    }
+
+    fn update_changes(&mut self) {
+        if let Some(start) = &self.turn_start {
+            self.changed = start.diff(&self.sim.snapshot());
         }
     }
 
+    /// Roll back the last placement or turn, refunding any money earned since.
+    pub fn undo(&mut self, ui: &mut Ui) -> Result<(), GameError> {
+        let current = self.snapshot(ui);
+        if let Some(s) = self.history.undo(current) {
+            self.load_snapshot(s, ui)?;
+            ui.record(Action::Undo);
+        }
+        Ok(())
+    }
+
+    pub fn redo(&mut self, ui: &mut Ui) -> Result<(), GameError> {
+        let current = self.snapshot(ui);
+        if let Some(s) = self.history.redo(current) {
+            self.load_snapshot(s, ui)?;
+            ui.record(Action::Redo);
+        }
+        Ok(())
+    }
+
     fn skip_pressed(&self, input: &Controls) -> bool {
         self.skip_btn.clicked() || input.kbm.press(Key::N) || input.kbm.press(Key::Space) ||
             input.kbm.press(Key::Return)
     }
 
-    pub fn update(&mut self, input: &Controls, ui: &mut Ui) {
+    pub fn update(&mut self, input: &Controls, ui: &mut Ui) -> Result<(), GameError> {
         self.btn_clicked = false;
         for d in self.dir_btns.iter_mut() {
             d.update(input);
@@ -125,88 +234,154 @@ impl Board {
                 self.btn_clicked = true;
             }
         }
-        if self.turns_to_change > 0 {
+        for b in self.undo_btns.iter_mut() {
+            b.update(input);
+            if b.clicked() {
+                self.btn_clicked = true;
+            }
+        }
+        if self.undo_btns[0].clicked() || input.kbm.press(Key::Z) {
+            self.undo(ui)?;
+        } else if self.undo_btns[1].clicked() || input.kbm.press(Key::Y) {
+            self.redo(ui)?;
+        }
+        if self.sim.in_turn() {
             self.turn_timer += input.frame_elapsed;
             if self.turn_timer > TURN_LENGTH {
                 self.turn_timer = 0.0;
-                self.skip(ui);
-
+                let harvested = self.sim.advance_step().harvested;
+                Self::harvest(ui, harvested);
+                self.update_changes();
             }
             self.skip_btn.update(input);
             if  self.skip_pressed(input){
-                while self.turns_to_change > 0 {
-                    self.skip(ui);
-                }
+                let harvested = self.sim.advance_turn().harvested;
+                Self::harvest(ui, harvested);
+                self.update_changes();
             }
-            self.check_carrot();
         } else {
-            self.btn_update(input, ui);
+            self.btn_update(input, ui)?;
         }
-    
+        self.sim.check(ui.get_money());
+        ui.set_objectives(&self.sim.objective_progress(ui.get_money()));
+        ui.purchases_mut().set_turn(self.sim.turn_count());
+        Ok(())
     }
 
-    fn btn_update(&mut self, input: &Controls, ui: &mut Ui) {
+    fn btn_update(&mut self, input: &Controls, ui: &mut Ui) -> Result<(), GameError> {
 
         self.next_btn.update(input);
         if self.skip_pressed(input) {
             self.btn_clicked = true;
-            self.turns_to_change = STEPS_PER_TURN;
+            let before = self.snapshot(ui);
+            self.history.push(before);
+            self.turn_start = Some(self.sim.snapshot());
+            self.changed = MapDiff::default();
+            self.sim.start_turn();
+            self.patrolling = None;
+            ui.record(Action::NextTurn);
         }
+        self.show_paths = ui.tool() == Tool::Patrol;
+        if !self.show_paths {
+            self.patrolling = None;
+        }
+        self.held = match ui.tool() {
+            Tool::Place => ui.active_tile(),
+            _ => Tiles::None,
+        };
 
         self.set_cursor(input.kbm.mouse_pos());
-        if !self.btn_clicked && self.is_selected && input.kbm.mouse_press(MouseButton::Left)
-            && ui.get_tile() != Tiles::None {
-                self.place_tile(ui);
+        if !self.btn_clicked && !ui.hotbar_clicked() && self.is_selected
+            && input.kbm.mouse_press(MouseButton::Left) {
+                match ui.tool() {
+                    Tool::Sell => self.sell_tile(ui)?,
+                    Tool::Patrol => self.patrol(ui)?,
+                    Tool::Place if ui.active_tile() != Tiles::None => self.place_tile(ui)?,
+                    Tool::Place => (),
+                }
             }
+        Ok(())
     }
 
-    fn place_tile(&mut self, ui: &mut Ui) {
-        let i = self.board.bi(
-            self.selected.0, self.selected.1);
-        let to_place = ui.get_tile();
-        let prev_tile = self.board.map[i];
-
-        if to_place == Tiles::Key {
-            if prev_tile == Tiles::Door {
-                self.complete = true;
-            }
-            else {
-                return;
-            }
-        }
-        
-        if prev_tile != Tiles::None && prev_tile != Tiles::Door {
-            self.set(Choice { i,
-                              x: self.selected.0, y: self.selected.1,
-                              src: Tiles::None, dst: ui.pop_tile() });
+    /// Sell the selected tile for part of its shop price.
+    fn sell_tile(&mut self, ui: &mut Ui) -> Result<(), GameError> {
+        let (x, y) = self.selected;
+        if !self.sim.can_sell(x, y) {
+            return Ok(());
         }
+        let before = self.snapshot(ui);
+        let step = self.sim.step_count();
+        let tile = self.sim.sell_tile(x, y)?;
+        let refund = self.catalogue.sell_price(tile);
+        ui.set_money(ui.get_money() + refund);
+        ui.record(Action::Sell { x, y, step, refund });
+        self.sim.check(ui.get_money());
+        self.history.push(before);
+        self.update_changes();
+        Ok(())
     }
 
-    fn step(&mut self, ui: &mut Ui) {
-        for t in self.obj_map.iter_mut() {
-            t.update(&mut self.board);
-            if t.removed() {
-                *t = self.board.set_tile_obj(Tiles::Grass, t.pos().0, t.pos().1);
-            }
-        }
-        let choices: Vec<Choice> = self.board.map_updates
-            .drain(0..self.board.map_updates.len()).collect();
-        for c in choices {
-            let current_tile = self.obj_map[c.i].tile();
-            if current_tile != c.dst {
-                if current_tile == Tiles::Grass && c.dst != Tiles::Ice {
-                    self.set(c);
-                } else  {
-                    self.obj_map[c.i].interact(c.dst);
-                    if self.obj_map[c.i].removed() {
-                        self.set(c);
-                    }
+    /// Clicking a goat picks it, clicking other cells adds them to the picked
+    /// goat's patrol path and clicking the picked goat again clears its path.
+    fn patrol(&mut self, ui: &mut Ui) -> Result<(), GameError> {
+        let (x, y) = self.selected;
+        let goat = match self.patrolling {
+            Some(g) if g != (x, y) && !self.sim.can_patrol(x, y) => g,
+            _ => {
+                if self.patrolling == Some((x, y)) {
+                    self.patrolling = None;
+                    self.edit_path(ui, x, y, None)?;
+                } else if self.sim.can_patrol(x, y) {
+                    self.patrolling = Some((x, y));
                 }
-            }
+                return Ok(());
+            },
+        };
+        self.edit_path(ui, goat.0, goat.1, Some((x, y)))
+    }
+
+    /// Add `to` to the path of the goat at `(x, y)`, or clear it if `to` is `None`.
+    fn edit_path(&mut self, ui: &mut Ui, x: usize, y: usize, to: Option<(usize, usize)>) -> Result<(), GameError> {
+        let before = self.snapshot(ui);
+        let step = self.sim.step_count();
+        let action = match to {
+            Some(to) => {
+                self.sim.add_waypoint(x, y, to)?;
+                Action::Waypoint { x, y, step, to }
+            },
+            None => {
+                self.sim.set_path(x, y, Vec::new())?;
+                Action::ClearPath { x, y, step }
+            },
+        };
+        ui.record(action);
+        self.history.push(before);
+        Ok(())
+    }
+
+    fn place_tile(&mut self, ui: &mut Ui) -> Result<(), GameError> {
+        let (x, y) = self.selected;
+        let tile = ui.active_tile();
+        let before = self.snapshot(ui);
+        let step = self.sim.step_count();
+        let placed = self.sim.place_tile(tile, x, y)?;
+        ui.record(Action::Place { tile, x, y, step });
+        if placed {
+            ui.take_tile(tile);
+        }
+        self.sim.check(ui.get_money());
+        if placed || self.sim.complete {
+            self.history.push(before);
+        }
+        if placed {
+            self.update_changes();
         }
-        if self.board.harvestable.len() > 0 {
-            ui.set_money(ui.get_money() + self.board.harvestable.len());
-            self.board.harvestable.clear();
+        Ok(())
+    }
+
+    fn harvest(ui: &mut Ui, harvested: usize) {
+        if harvested > 0 {
+            ui.set_money(ui.get_money() + harvested);
         }
     }
 
@@ -215,7 +390,7 @@ impl Board {
         let x: i64 = (pos.x / TILE.x) as i64;
         let y: i64 = (pos.y / TILE.y) as i64;
 
-        if x < 0 || y < 0 || x >= self.board.w as i64 || y >= self.board.h as i64 {
+        if x < 0 || y < 0 || x >= self.sim.board.w as i64 || y >= self.sim.board.h as i64 {
             self.is_selected = false;
             return;
         }
@@ -225,12 +400,6 @@ impl Board {
         self.outline.rect.y = self.selected.1 as f64 * TILE.y;
     }
 
-    fn set(&mut self, c: Choice) {
-        //println!("x:{}, y:{}", c.x, c.y);
-        self.obj_map[c.i] = self.board.set_tile_obj(c.dst, c.x, c.y);
-        self.board.map[c.i] = c.dst;
-    }
-
     pub fn draw(&self, cam: &mut Camera) {
         match &self.map {
             None => (),
@@ -246,14 +415,21 @@ impl Board {
             }
         }
         self.draw_map(cam);
+        self.draw_changes(cam);
+        self.draw_paths(cam);
         if self.is_selected {
+            self.draw_aura(cam);
+            self.draw_footprint(cam);
             cam.draw(&self.outline);
         }
 
         for d in self.dir_btns.iter() {
             d.draw(cam);
         }
-        if self.turns_to_change  > 0 {
+        for b in self.undo_btns.iter() {
+            b.draw(cam);
+        }
+        if self.sim.in_turn() {
             self.skip_btn.draw(cam);
         } else {
             self.next_btn.draw(cam);
@@ -261,17 +437,114 @@ impl Board {
     }
 
     fn draw_map(&self, cam: &mut Camera) {
-        for obj in self.obj_map.iter() {
-            obj.draw(cam);
+        for obj in self.sim.tile_objs().iter() {
+            self.sprites.draw(cam, obj.as_ref());
+        }
+        for s in self.sim.board.structures().iter() {
+            self.sprites.draw_structure(cam, s);
+        }
+    }
+
+    /// Shade the cells reached by the aura of the hovered tile,
+    /// or of the held tile if it were placed there.
+    fn draw_aura(&self, cam: &mut Camera) {
+        let (x, y) = self.selected;
+        let board = &self.sim.board;
+        let r = match board.rules.get(self.held).aura()
+            .or_else(|| board.rules.get(board.get(x, y)).aura()) {
+            Some(r) => r,
+            None => return,
+        };
+        let (x0, y0) = (x.saturating_sub(r), y.saturating_sub(r));
+        let (x1, y1) = ((x + r).min(board.w - 1), (y + r).min(board.h - 1));
+        let mut go = self.highlight;
+        go.rect.x = x0 as f64 * TILE.x;
+        go.rect.y = y0 as f64 * TILE.y;
+        go.rect.w = (x1 + 1 - x0) as f64 * TILE.x;
+        go.rect.h = (y1 + 1 - y0) as f64 * TILE.y;
+        go.colour = Colour::new(255, 220, 120, 60);
+        cam.draw(&go);
+    }
+
+    /// Shade the cells a held structure would cover,
+    /// red if it can't be built there.
+    fn draw_footprint(&self, cam: &mut Camera) {
+        let (w, h) = match self.sim.board.rules.get(self.held).footprint() {
+            Some(f) => f,
+            None => return,
+        };
+        let (x, y) = self.selected;
+        let mut go = self.highlight;
+        go.rect.x = x as f64 * TILE.x;
+        go.rect.y = y as f64 * TILE.y;
+        go.rect.w = w as f64 * TILE.x;
+        go.rect.h = h as f64 * TILE.y;
+        go.colour = if self.sim.board.can_build(self.held, x, y) {
+            Colour::new(120, 255, 120, 70)
+        } else {
+            Colour::new(255, 80, 80, 90)
+        };
+        cam.draw(&go);
+    }
+
+    /// Tint the cells that changed this turn and structures built or
+    /// demolished, cells that only changed internally get a fainter tint.
+    fn draw_changes(&self, cam: &mut Camera) {
+        let mut go = self.highlight;
+        go.colour = Colour::new(255, 230, 90, 90);
+        for s in self.changed.built.iter().chain(self.changed.demolished.iter()) {
+            go.rect.x = s.x as f64 * TILE.x;
+            go.rect.y = s.y as f64 * TILE.y;
+            go.rect.w = s.w as f64 * TILE.x;
+            go.rect.h = s.h as f64 * TILE.y;
+            cam.draw(&go);
+        }
+        go.rect.w = TILE.x;
+        go.rect.h = TILE.y;
+        for c in self.changed.cells.iter() {
+            go.rect.x = c.x as f64 * TILE.x;
+            go.rect.y = c.y as f64 * TILE.y;
+            cam.draw(&go);
+        }
+        go.colour = Colour::new(255, 230, 90, 35);
+        for (x, y) in self.changed.counters.iter() {
+            go.rect.x = *x as f64 * TILE.x;
+            go.rect.y = *y as f64 * TILE.y;
+            cam.draw(&go);
+        }
+    }
+
+    /// Mark the cells of each goat's patrol path while the patrol tool
+    /// is in use, brighter for the goat that is picked.
+    fn draw_paths(&self, cam: &mut Camera) {
+        if !self.show_paths {
+            return;
+        }
+        let mut go = self.highlight;
+        go.rect.w = TILE.x;
+        go.rect.h = TILE.y;
+        for obj in self.sim.tile_objs().iter().filter(|o| !o.path().is_empty()) {
+            let alpha = if self.patrolling == Some(obj.pos()) { 140 } else { 60 };
+            go.colour = Colour::new(120, 140, 255, alpha);
+            for (x, y) in obj.path().iter() {
+                go.rect.x = *x as f64 * TILE.x;
+                go.rect.y = *y as f64 * TILE.y;
+                cam.draw(&go);
+            }
+        }
+        if let Some((x, y)) = self.patrolling {
+            go.colour = Colour::new(120, 140, 255, 140);
+            go.rect.x = x as f64 * TILE.x;
+            go.rect.y = y as f64 * TILE.y;
+            cam.draw(&go);
         }
     }
 
     fn _debug_tilemap(&self, cam: &mut Camera) {
-        for x in 0..self.board.w {
-            for y in 0..self.board.h {
-                let i = self.board.bi(x, y);
-                if self.obj_map[i].tile() == Tiles::Grass {
-                    let mut go = self.board.resources[&Tiles::None][0];
+        for x in 0..self.sim.board.w {
+            for y in 0..self.sim.board.h {
+                if self.sim.board.get(x, y) == Tiles::Grass {
+                    let mut go = self.sprites.resources[&Tiles::None][0];
                     go.rect.x = x as f64 * TILE.x;
                     go.rect.y = y as f64 * TILE.y;
                     cam.draw(
@@ -289,8 +562,8 @@ impl Board {
     pub fn set_cam(&self, cam: &mut Camera) {
         cam.set_offset(
             Vec2::new(
-                (self.board.w as f64 * TILE.x / 2.0) - cam.get_view_size().x/2.0,
-                (self.board.h as f64 * TILE.y / 2.0) - cam.get_view_size().y/2.0,
+                (self.sim.board.w as f64 * TILE.x / 2.0) - cam.get_view_size().x/2.0,
+                (self.sim.board.h as f64 * TILE.y / 2.0) - cam.get_view_size().y/2.0,
             )
         );
     }
@@ -327,10 +600,10 @@ impl Board {
 
         
         let mut pos = off;
-        if pos.x < 0.0 || pos.x > self.board.w as f64 * TILE.x - cam.get_view_size().x {
+        if pos.x < 0.0 || pos.x > self.sim.board.w as f64 * TILE.x - cam.get_view_size().x {
             pos.x = cam.get_offset().x;
         }
-        if pos.y < 0.0 || pos.y > self.board.h as f64 * TILE.y - cam.get_view_size().y {
+        if pos.y < 0.0 || pos.y > self.sim.board.h as f64 * TILE.y - cam.get_view_size().y {
             pos.y = cam.get_offset().y;
         }
         cam.set_offset(pos);
@@ -346,25 +619,46 @@ fn get_dir_btn(render: &mut Render) -> Result<[Button; 4], Error> {
     let size = TILE;
     Ok([
         Button::new(
-            Tilemap::get_tile(dirs, 0, 0),
-            Tilemap::get_tile(dirs, 0, 1),
+            TileSprites::get_tile(dirs, 0, 0),
+            TileSprites::get_tile(dirs, 0, 1),
             BTN_MID+ Vec2::new(0.0, - size.y)
         ),
                 Button::new(
-                    Tilemap::get_tile(dirs, 2, 0),
-                    Tilemap::get_tile(dirs, 2, 1),
+                    TileSprites::get_tile(dirs, 2, 0),
+                    TileSprites::get_tile(dirs, 2, 1),
                     BTN_MID + Vec2::new(0.0, size.y)
                 ),
         Button::new(
-            Tilemap::get_tile(dirs, 1, 0),
-            Tilemap::get_tile(dirs, 1, 1),
+            TileSprites::get_tile(dirs, 1, 0),
+            TileSprites::get_tile(dirs, 1, 1),
             BTN_MID+ Vec2::new(-size.x, 0.0)
                 ),
         Button::new(
-            Tilemap::get_tile(dirs, 3, 0),
-            Tilemap::get_tile(dirs, 3, 1),
+            TileSprites::get_tile(dirs, 3, 0),
+            TileSprites::get_tile(dirs, 3, 1),
             BTN_MID + Vec2::new(size.x, 0.0)
                 ),
 
         ])
 }
+
+const UNDO_POS: Vec2 = Vec2::new(350.0, 297.0);
+
+/// Undo and redo buttons, drawn as left and right arrows.
+fn get_undo_btn(render: &mut Render) -> Result<[Button; 2], Error> {
+    let dirs = render.texture_manager.load(
+                    Path::new("resources/textures/btn/dir.png")
+    )?;
+    Ok([
+        Button::new(
+            TileSprites::get_tile(dirs, 1, 0),
+            TileSprites::get_tile(dirs, 1, 1),
+            UNDO_POS
+        ),
+        Button::new(
+            TileSprites::get_tile(dirs, 3, 0),
+            TileSprites::get_tile(dirs, 3, 1),
+            UNDO_POS + Vec2::new(TILE.x, 0.0)
+        ),
+    ])
+}
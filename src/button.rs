@@ -45,6 +45,11 @@ impl Button {
         self.clicked
     }
 
+    /// The mouse is over the button.
+    pub fn hovered(&self) -> bool {
+        self.selected
+    }
+
     pub fn held(&self) -> bool {
         self.held
     }
@@ -0,0 +1,95 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Deserialize};
+
+/// One map in the campaign.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Level {
+    pub name: String,
+    pub map: PathBuf,
+    /// money the player starts the level with
+    pub money: usize,
+}
+
+#[derive(Debug)]
+pub enum CampaignError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Empty,
+}
+
+impl fmt::Display for CampaignError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CampaignError::Io(e) => write!(f, "could not read campaign manifest: {}", e),
+            CampaignError::Parse(e) => write!(f, "could not parse campaign manifest: {}", e),
+            CampaignError::Empty => write!(f, "campaign manifest has no levels"),
+        }
+    }
+}
+
+impl std::error::Error for CampaignError {}
+
+/// An ordered list of levels, beating a level unlocks the next one.
+#[derive(Clone, Debug)]
+pub struct Campaign {
+    pub levels: Vec<Level>,
+    current: usize,
+    unlocked: usize,
+}
+
+impl Campaign {
+    pub fn new(levels: Vec<Level>) -> Result<Campaign, CampaignError> {
+        if levels.is_empty() {
+            return Err(CampaignError::Empty);
+        }
+        Ok(Campaign { levels, current: 0, unlocked: 0 })
+    }
+
+    /// Load a manifest, a RON list of `Level`s.
+    pub fn load(path: &Path) -> Result<Campaign, CampaignError> {
+        let text = std::fs::read_to_string(path).map_err(CampaignError::Io)?;
+        Campaign::new(ron::from_str(&text).map_err(CampaignError::Parse)?)
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn level(&self) -> &Level {
+        &self.levels[self.current]
+    }
+
+    /// Index of the furthest level the player can pick.
+    pub fn unlocked(&self) -> usize {
+        self.unlocked
+    }
+
+    /// Make level `i` the current one, if it has been unlocked.
+    pub fn select(&mut self, i: usize) -> Option<&Level> {
+        if i > self.unlocked || i >= self.levels.len() {
+            return None;
+        }
+        self.current = i;
+        Some(self.level())
+    }
+
+    /// Make the level played on `map` the current one and unlock it,
+    /// for a game that was picked up without going through `select`.
+    pub fn resume(&mut self, map: &Path) -> Option<&Level> {
+        self.current = self.levels.iter().position(|l| l.map == map)?;
+        self.unlocked = self.unlocked.max(self.current);
+        Some(self.level())
+    }
+
+    /// Mark the current level as won and move on, `None` once the campaign is finished.
+    pub fn advance(&mut self) -> Option<&Level> {
+        if self.current + 1 >= self.levels.len() {
+            return None;
+        }
+        self.current += 1;
+        self.unlocked = self.unlocked.max(self.current);
+        Some(self.level())
+    }
+}
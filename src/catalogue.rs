@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Deserialize};
+
+use crate::error::GameError;
+use crate::tiles::Tiles;
+
+const DEFAULT_CATALOGUE: &str = include_str!("../resources/shop.ron");
+
+/// How an item's price goes up each time it is bought.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Pricing {
+    /// always `cost`
+    #[default]
+    Fixed,
+    /// `cost` plus this much for each one bought
+    Linear(usize),
+    /// `cost` raised by this percent for each one bought, compounding
+    Percent(usize),
+}
+
+/// Something the shop sells.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Item {
+    pub tile: Tiles,
+    /// price of the first one bought
+    pub cost: usize,
+    #[serde(default)]
+    pub pricing: Pricing,
+    /// button texture
+    pub icon: PathBuf,
+    /// button texture while hovered
+    pub icon_active: PathBuf,
+    /// how many can be bought in a level, unlimited if not set
+    #[serde(default)]
+    pub stock: Option<usize>,
+}
+
+#[derive(Debug)]
+pub enum CatalogueError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    /// a tile named in a map property that isn't a `Tiles`
+    UnknownTile(String),
+    /// a map property that isn't a `Pricing`
+    UnknownPricing(String),
+}
+
+impl fmt::Display for CatalogueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CatalogueError::Io(e) => write!(f, "could not read shop catalogue: {}", e),
+            CatalogueError::Parse(e) => write!(f, "could not parse shop catalogue: {}", e),
+            CatalogueError::UnknownTile(t) => write!(f, "shop has unknown tile \"{}\"", t),
+            CatalogueError::UnknownPricing(p) => write!(f, "shop has unknown pricing \"{}\"", p),
+        }
+    }
+}
+
+impl std::error::Error for CatalogueError {}
+
+impl Item {
+    /// The price once `bought` have already been bought.
+    pub fn price(&self, bought: usize) -> usize {
+        match self.pricing {
+            Pricing::Fixed => self.cost,
+            Pricing::Linear(step) => self.cost.saturating_add(step.saturating_mul(bought)),
+            Pricing::Percent(p) => (0..bought).fold(self.cost, |c, _| {
+                c.saturating_mul(100 + p) / 100
+            }),
+        }
+    }
+}
+
+/// Everything for sale in the shop, in the order the buttons are laid out.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Catalogue {
+    /// buttons in each row of the shop
+    pub columns: usize,
+    /// prices only go up with what was bought in the current turn,
+    /// stock still lasts the whole level
+    #[serde(default)]
+    pub reset_each_turn: bool,
+    /// percent of a tile's starting price given back when it's sold
+    #[serde(default = "default_refund")]
+    pub refund_percent: usize,
+    pub items: Vec<Item>,
+}
+
+fn default_refund() -> usize {
+    50
+}
+
+impl Default for Catalogue {
+    fn default() -> Self {
+        Catalogue::parse(DEFAULT_CATALOGUE).expect("default shop catalogue is invalid")
+    }
+}
+
+impl Catalogue {
+    pub fn load(path: &Path) -> Result<Catalogue, CatalogueError> {
+        let text = std::fs::read_to_string(path).map_err(CatalogueError::Io)?;
+        Catalogue::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Catalogue, CatalogueError> {
+        ron::from_str(text).map_err(CatalogueError::Parse)
+    }
+
+    /// This catalogue changed by a level's map properties,
+    /// `dir` is the folder the map was loaded from.
+    ///
+    /// `shop` replaces the catalogue with another file, `shop_items` is a comma
+    /// separated list of the tiles to sell, `cost_<Tile>`, `stock_<Tile>` and
+    /// `pricing_<Tile>` set the price, stock and pricing of a single tile,
+    /// `reset_prices` sets `reset_each_turn` and `refund_percent` sets `refund_percent`.
+    pub fn for_map(&self, props: &tiled::Properties, dir: &Path) -> Result<Catalogue, CatalogueError> {
+        let mut catalogue = match props.strings.get("shop") {
+            Some(file) => Catalogue::load(&dir.join(file))?,
+            None => self.clone(),
+        };
+        if let Some(list) = props.strings.get("shop_items") {
+            let mut items = Vec::new();
+            for name in list.split(',').map(|n| n.trim()).filter(|n| !n.is_empty()) {
+                let tile = tile_named(name)?;
+                if let Some(item) = catalogue.items.iter().find(|i| i.tile == tile) {
+                    items.push(item.clone());
+                }
+            }
+            catalogue.items = items;
+        }
+        for (name, value) in props.integers.iter() {
+            let value = (*value).max(0) as usize;
+            if name == "refund_percent" {
+                catalogue.refund_percent = value;
+            } else if let Some(tile) = name.strip_prefix("cost_") {
+                let tile = tile_named(tile)?;
+                for item in catalogue.items.iter_mut().filter(|i| i.tile == tile) {
+                    item.cost = value;
+                }
+            } else if let Some(tile) = name.strip_prefix("stock_") {
+                let tile = tile_named(tile)?;
+                for item in catalogue.items.iter_mut().filter(|i| i.tile == tile) {
+                    item.stock = Some(value);
+                }
+            }
+        }
+        for (name, value) in props.strings.iter() {
+            if let Some(tile) = name.strip_prefix("pricing_") {
+                let tile = tile_named(tile)?;
+                let pricing: Pricing = ron::from_str(value)
+                    .map_err(|_| CatalogueError::UnknownPricing(value.clone()))?;
+                for item in catalogue.items.iter_mut().filter(|i| i.tile == tile) {
+                    item.pricing = pricing;
+                }
+            }
+        }
+        if let Some(reset) = props.booleans.get("reset_prices") {
+            catalogue.reset_each_turn = *reset;
+        }
+        Ok(catalogue)
+    }
+
+    /// This catalogue changed by the properties of the `.tmx` file at `map`.
+    pub fn for_map_file(&self, map: &Path) -> Result<Catalogue, GameError> {
+        let tiled_map = tiled::Map::new(map)
+            .map_err(|e| GameError::Map(format!("{}: {:?}", map.display(), e)))?;
+        Ok(self.for_map(&tiled_map.props, map.parent().unwrap_or(Path::new("")))?)
+    }
+
+    /// What `tile` costs after `purchases`, None if it isn't sold.
+    pub fn price(&self, tile: Tiles, purchases: &Purchases) -> Option<usize> {
+        let bought = if self.reset_each_turn {
+            purchases.bought_this_turn(tile)
+        } else {
+            purchases.bought(tile)
+        };
+        self.get(tile).map(|i| i.price(bought))
+    }
+
+    /// How many of `tile` are left to buy, None if there's no limit.
+    pub fn stock_left(&self, tile: Tiles, purchases: &Purchases) -> Option<usize> {
+        self.get(tile)?.stock.map(|s| s.saturating_sub(purchases.bought(tile)))
+    }
+
+    /// What `tile` costs after `purchases`, None if it isn't sold,
+    /// is out of stock or costs more than `money`.
+    pub fn affordable(&self, tile: Tiles, money: usize, purchases: &Purchases) -> Option<usize> {
+        if self.stock_left(tile, purchases) == Some(0) {
+            return None;
+        }
+        self.price(tile, purchases).filter(|c| *c <= money)
+    }
+
+    /// Money given back for selling a placed `tile`, nothing if the shop doesn't sell it.
+    pub fn sell_price(&self, tile: Tiles) -> usize {
+        self.get(tile).map(|i| i.cost * self.refund_percent / 100).unwrap_or(0)
+    }
+
+    pub fn get(&self, tile: Tiles) -> Option<&Item> {
+        self.items.iter().find(|i| i.tile == tile)
+    }
+}
+
+/// What has been bought from the shop during a level,
+/// which sets the prices and the stock left.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Purchases {
+    /// bought this level
+    level: HashMap<Tiles, usize>,
+    /// bought during `turn`
+    this_turn: HashMap<Tiles, usize>,
+    turn: usize,
+}
+
+impl Purchases {
+    pub fn new() -> Purchases {
+        Purchases::default()
+    }
+
+    /// Count purchases as made during `turn`, starting the
+    /// turn's counts again if it's a different turn.
+    pub fn set_turn(&mut self, turn: usize) {
+        if turn != self.turn {
+            self.turn = turn;
+            self.this_turn.clear();
+        }
+    }
+
+    pub fn bought(&self, tile: Tiles) -> usize {
+        self.level.get(&tile).copied().unwrap_or(0)
+    }
+
+    pub fn bought_this_turn(&self, tile: Tiles) -> usize {
+        self.this_turn.get(&tile).copied().unwrap_or(0)
+    }
+
+    pub fn buy(&mut self, tile: Tiles) {
+        *self.level.entry(tile).or_default() += 1;
+        *self.this_turn.entry(tile).or_default() += 1;
+    }
+}
+
+fn tile_named(name: &str) -> Result<Tiles, CatalogueError> {
+    ron::from_str(name).map_err(|_| CatalogueError::UnknownTile(name.to_string()))
+}
@@ -0,0 +1,105 @@
+use std::fmt;
+
+use crate::campaign::CampaignError;
+use crate::catalogue::CatalogueError;
+use crate::replay::ReplayError;
+use crate::save::SaveError;
+use crate::tiles::{Tiles, RulesError};
+
+/// Anything that can go wrong while loading or playing a level.
+#[derive(Debug)]
+pub enum GameError {
+    /// the map file couldn't be read
+    Map(String),
+    /// the map has no layer with the GameState property
+    NoGameState,
+    /// a GameState layer id that isn't a game tile
+    UnknownTile { x: usize, y: usize, id: u32 },
+    /// the map has no tileset with this name
+    MissingTileset(String),
+    /// a tile in the game_state tileset whose type isn't a `Tiles`
+    UnknownTileType { id: u32, name: String },
+    /// the GameState layer isn't the same size as the map
+    LayerSize { width: usize, height: usize, tiles: usize },
+    OutOfRange { x: usize, y: usize },
+    /// a tile the player can't hold
+    NotForSale(Tiles),
+    /// the tile here wasn't placed by the player or marked sellable
+    NotSellable { x: usize, y: usize },
+    /// the tile here can't be given a path to follow
+    NotMovable { x: usize, y: usize },
+    Rules(RulesError),
+    Save(SaveError),
+    Replay(ReplayError),
+    Campaign(CampaignError),
+    Catalogue(CatalogueError),
+    #[cfg(feature = "sdl")]
+    Sdl(sdl_helper::Error),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::Map(e) => write!(f, "could not load map: {}", e),
+            GameError::NoGameState => write!(f, "map has no layer with the GameState property"),
+            GameError::UnknownTile { x, y, id } => write!(
+                f, "map has unknown tile id {} at ({}, {})", id, x, y),
+            GameError::MissingTileset(name) => write!(f, "map has no tileset named \"{}\"", name),
+            GameError::UnknownTileType { id, name } => write!(
+                f, "tile {} of the game_state tileset has unknown type \"{}\"", id, name),
+            GameError::LayerSize { width, height, tiles } => write!(
+                f, "GameState layer is {}x{} with {} tiles, which doesn't match the map",
+                width, height, tiles),
+            GameError::OutOfRange { x, y } => write!(f, "({}, {}) is not on the board", x, y),
+            GameError::NotForSale(t) => write!(f, "{:?} can't be bought", t),
+            GameError::NotSellable { x, y } => write!(f, "the tile at ({}, {}) can't be sold", x, y),
+            GameError::NotMovable { x, y } => write!(f, "the tile at ({}, {}) can't follow a path", x, y),
+            GameError::Rules(e) => write!(f, "{}", e),
+            GameError::Save(e) => write!(f, "{}", e),
+            GameError::Replay(e) => write!(f, "{}", e),
+            GameError::Campaign(e) => write!(f, "{}", e),
+            GameError::Catalogue(e) => write!(f, "{}", e),
+            #[cfg(feature = "sdl")]
+            GameError::Sdl(e) => write!(f, "{:?}", e),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+impl From<RulesError> for GameError {
+    fn from(e: RulesError) -> Self {
+        GameError::Rules(e)
+    }
+}
+
+impl From<SaveError> for GameError {
+    fn from(e: SaveError) -> Self {
+        GameError::Save(e)
+    }
+}
+
+impl From<ReplayError> for GameError {
+    fn from(e: ReplayError) -> Self {
+        GameError::Replay(e)
+    }
+}
+
+impl From<CampaignError> for GameError {
+    fn from(e: CampaignError) -> Self {
+        GameError::Campaign(e)
+    }
+}
+
+impl From<CatalogueError> for GameError {
+    fn from(e: CatalogueError) -> Self {
+        GameError::Catalogue(e)
+    }
+}
+
+#[cfg(feature = "sdl")]
+impl From<sdl_helper::Error> for GameError {
+    fn from(e: sdl_helper::Error) -> Self {
+        GameError::Sdl(e)
+    }
+}
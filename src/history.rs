@@ -0,0 +1,56 @@
+use crate::catalogue::Purchases;
+use crate::inventory::Inventory;
+use crate::sim::SimState;
+
+/// How many actions can be undone.
+const MAX_UNDO: usize = 100;
+
+/// Everything an action can change, taken just before the action.
+#[derive(Clone)]
+pub struct Snapshot {
+    pub sim: SimState,
+    pub money: usize,
+    pub inventory: Inventory,
+    pub purchases: Purchases,
+}
+
+/// Undo and redo stacks of snapshots.
+#[derive(Clone, Default)]
+pub struct History {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+}
+
+impl History {
+    pub fn new() -> History {
+        History { undo: Vec::new(), redo: Vec::new() }
+    }
+
+    /// Remember the state before a new action, this forgets anything that could be redone.
+    pub fn push(&mut self, before: Snapshot) {
+        if self.undo.len() == MAX_UNDO {
+            self.undo.remove(0);
+        }
+        self.undo.push(before);
+        self.redo.clear();
+    }
+
+    /// Returns the state to go back to, `current` is kept so it can be redone.
+    pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let s = self.undo.pop()?;
+        self.redo.push(current);
+        Some(s)
+    }
+
+    /// Returns the state to go forward to, `current` is kept so it can be undone again.
+    pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let s = self.redo.pop()?;
+        self.undo.push(current);
+        Some(s)
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
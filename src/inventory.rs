@@ -0,0 +1,91 @@
+use serde::{Serialize, Deserialize};
+
+use crate::tiles::Tiles;
+
+/// Tiles the player has bought but not placed yet, a counted stack
+/// of each kind in the order they were first bought.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Inventory {
+    stacks: Vec<(Tiles, usize)>,
+    /// the tile placed when clicking on the board
+    active: Tiles,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Inventory::new()
+    }
+}
+
+impl Inventory {
+    pub fn new() -> Inventory {
+        Inventory { stacks: Vec::new(), active: Tiles::None }
+    }
+
+    /// Add one `tile`, it becomes active if nothing was.
+    pub fn add(&mut self, tile: Tiles) {
+        match self.stacks.iter_mut().find(|(t, _)| *t == tile) {
+            Some((_, n)) => *n += 1,
+            None => self.stacks.push((tile, 1)),
+        }
+        if self.active == Tiles::None {
+            self.active = tile;
+        }
+    }
+
+    /// Remove one `tile`, false if there wasn't one. Once a stack runs
+    /// out the active tile moves to the stack that took its slot.
+    pub fn take(&mut self, tile: Tiles) -> bool {
+        let i = match self.stacks.iter().position(|(t, _)| *t == tile) {
+            Some(i) => i,
+            None => return false,
+        };
+        self.stacks[i].1 -= 1;
+        if self.stacks[i].1 == 0 {
+            self.stacks.remove(i);
+            if self.active == tile {
+                self.active = self.stacks.get(i).or(self.stacks.last())
+                    .map(|(t, _)| *t).unwrap_or(Tiles::None);
+            }
+        }
+        true
+    }
+
+    pub fn count(&self, tile: Tiles) -> usize {
+        self.stacks.iter().find(|(t, _)| *t == tile).map(|(_, n)| *n).unwrap_or(0)
+    }
+
+    /// The tile to place, `Tiles::None` if the inventory is empty.
+    pub fn active(&self) -> Tiles {
+        self.active
+    }
+
+    /// Make `tile` active, false if there isn't one.
+    pub fn select(&mut self, tile: Tiles) -> bool {
+        if self.count(tile) == 0 {
+            return false;
+        }
+        self.active = tile;
+        true
+    }
+
+    /// Make the stack in hotbar slot `slot` active, false if the slot is empty.
+    pub fn select_slot(&mut self, slot: usize) -> bool {
+        match self.stacks.get(slot) {
+            Some((t, _)) => {
+                self.active = *t;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Each kind of tile held and how many, in hotbar order.
+    pub fn stacks(&self) -> &[(Tiles, usize)] {
+        &self.stacks
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+}
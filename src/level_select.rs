@@ -0,0 +1,106 @@
+use std::path::Path;
+
+use sdl_helper::{GameObject, Render, Error, Camera, Colour, input::Controls, geometry::Vec2};
+
+use crate::button::Button;
+use crate::campaign::Campaign;
+use crate::tiles::TILE;
+use crate::ui::Ui;
+
+const PER_ROW: usize = 5;
+const SPACING: Vec2 = Vec2::new(50.0, 50.0);
+const FIRST_BTN: Vec2 = Vec2::new(130.0, 90.0);
+/// middle of the level name shown above the buttons
+const NAME_POS: Vec2 = Vec2::new(240.0, 45.0);
+
+struct LevelBtn {
+    btn: Button,
+    num: Vec<GameObject>,
+    name: Vec<GameObject>,
+}
+
+/// Screen listing the campaign's levels, locked levels are greyed out
+/// and the name of the level under the mouse is shown above them.
+pub struct LevelSelect {
+    bg: GameObject,
+    levels: Vec<LevelBtn>,
+    selected: Option<usize>,
+    hovered: Option<usize>,
+}
+
+impl LevelSelect {
+    pub fn new(render: &mut Render, campaign: &Campaign, ui: &mut Ui) -> Result<LevelSelect, Error> {
+        let mut bg = GameObject::new_from_tex(
+            render.texture_manager.load(
+                Path::new("resources/textures/tiles/blank.png"))?);
+        bg.rect.w = 500.0;
+        bg.rect.h = 500.0;
+        bg.colour = Colour::new(10, 10, 10, 200);
+        bg.parallax = Vec2::new(0.0, 0.0);
+        let normal = render.texture_manager.load(
+            Path::new("resources/textures/btn/next.png"))?;
+        let active = render.texture_manager.load(
+            Path::new("resources/textures/btn/next_active.png"))?;
+        let mut levels = Vec::new();
+        for (i, level) in campaign.levels.iter().enumerate() {
+            let pos = FIRST_BTN + Vec2::new(
+                (i % PER_ROW) as f64 * SPACING.x,
+                (i / PER_ROW) as f64 * SPACING.y);
+            let width = level.name.chars().count() as f64 * TILE.x * 0.5;
+            levels.push(LevelBtn {
+                btn: Button::new(
+                    GameObject::new_from_tex(normal),
+                    GameObject::new_from_tex(active),
+                    pos),
+                num: ui.get_nums(i + 1, pos + Vec2::new(10.0, -20.0)),
+                name: ui.get_text(&level.name, NAME_POS + Vec2::new(-width * 0.5, 0.0)),
+            });
+        }
+        Ok(LevelSelect {
+            bg,
+            levels,
+            selected: None,
+            hovered: None,
+        })
+    }
+
+    pub fn update(&mut self, input: &Controls, campaign: &Campaign) {
+        self.selected = None;
+        self.hovered = None;
+        for (i, l) in self.levels.iter_mut().enumerate() {
+            l.btn.update(input);
+            if l.btn.hovered() {
+                self.hovered = Some(i);
+            }
+            let unlocked = i <= campaign.unlocked();
+            l.btn.set_colour(if unlocked {
+                Colour::white()
+            } else {
+                Colour::new(100, 100, 100, 255)
+            });
+            if unlocked && l.btn.clicked() {
+                self.selected = Some(i);
+            }
+        }
+    }
+
+    /// The level clicked this frame.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn draw(&self, cam: &mut Camera) {
+        cam.draw(&self.bg);
+        for l in self.levels.iter() {
+            l.btn.draw(cam);
+            for n in l.num.iter() {
+                cam.draw(n);
+            }
+        }
+        if let Some(l) = self.hovered.map(|i| &self.levels[i]) {
+            for c in l.name.iter() {
+                cam.draw(c);
+            }
+        }
+    }
+}
@@ -1,5 +1,23 @@
+pub mod error;
 pub mod tiles;
+pub mod sim;
+pub mod objective;
+pub mod save;
+pub mod replay;
+pub mod campaign;
+pub mod catalogue;
+pub mod history;
+pub mod inventory;
+pub mod solver;
+pub mod batch;
+pub mod validate;
+#[cfg(feature = "sdl")]
 pub mod board;
+#[cfg(feature = "sdl")]
 pub mod shop;
+#[cfg(feature = "sdl")]
 pub mod button;
+#[cfg(feature = "sdl")]
 pub mod ui;
+#[cfg(feature = "sdl")]
+pub mod level_select;
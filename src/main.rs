@@ -1,9 +1,10 @@
-use std::path::Path;
-use sdl_helper::{input::{Controls, controller::Button}, Render, DrawingArea, Error, input::keyboard::Key, geometry::*, Camera, GameObject, audio::AudioManager, Colour};
-use bunny_patch::{board::Board, shop::Shop, button, ui::Ui, tiles::Tilemap};
+use std::path::{Path, PathBuf};
+use sdl_helper::{input::Controls, Render, DrawingArea, Error, input::keyboard::Key, geometry::*, Camera, GameObject, audio::AudioManager, Colour};
+use bunny_patch::{error::GameError, board::Board, shop::Shop, catalogue::{Catalogue, Purchases}, button, ui::Ui, tiles::{TileSprites, Rules}, save::SaveGame, replay::Replay, campaign::{Campaign, Level}, level_select::LevelSelect};
 
 #[derive (Eq, PartialEq)]
 enum GameState {
+    LevelSelect,
     Board,
     Shop,
     Lose,
@@ -12,8 +13,17 @@ enum GameState {
 
 const DEATH_TIME: f64 = 1.2;
 const WIN_FADE: f64 = 1.5;
+const QUICK_SAVE: &str = "saves/quick.ron";
+const LAST_REPLAY: &str = "replays/last.ron";
+const CAMPAIGN: &str = "resources/maps/campaign.ron";
+const SHOP: &str = "resources/shop.ron";
 
 pub fn main() -> Result<(), Error> {
+    // bunny_patch --replay <file> plays back a recorded run before handing over control
+    let args: Vec<String> = std::env::args().collect();
+    let replay_path = args.iter().position(|a| a == "--replay")
+        .and_then(|i| args.get(i + 1)).map(PathBuf::from);
+
     let (mut cam, drawing_area, context) = DrawingArea::new(
         "Bunny Veg",                              // window name
         Rect::new(0.0, 0.0, 480.0, 320.0), // window camera
@@ -30,16 +40,53 @@ pub fn main() -> Result<(), Error> {
     let win_sfx = audio.sfx.load(Path::new("resources/audio/end.mp3"))?;
     
 
-    let mut game_state = GameState::Board;
+    let mut game_state = GameState::LevelSelect;
+
+    let mut campaign = match Campaign::load(Path::new(CAMPAIGN)) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{}, using the default level", e);
+            Campaign::new(vec![Level {
+                name: String::from("Bunny Patch"),
+                map: PathBuf::from("resources/maps/env.tmx"),
+                money: 10,
+            }]).unwrap()
+        }
+    };
     
     let mut board = Board::new(&mut render)?;
-    board.load_map(Path::new("resources/maps/env.tmx"), &mut render)?;
-    board.set_cam(&mut cam);
+    board.sim.set_rules(match Rules::load(Path::new("resources/rules.ron")) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("{}, using default tile rules", e);
+            Rules::default()
+        }
+    });
 
-    let mut shop = Shop::new(&mut render)?;
+    let catalogue = match Catalogue::load(Path::new(SHOP)) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{}, using the default shop", e);
+            Catalogue::default()
+        }
+    };
+    let mut shop = Shop::new(&mut render, &catalogue)?;
 
     let mut ui = Ui::new(&mut render)?;
 
+    let mut level_select = LevelSelect::new(&mut render, &campaign, &mut ui)?;
+
+    if let Some(path) = replay_path {
+        // a replay that can't be played leaves the player at the level select
+        match start_replay(&path, &mut board, &mut shop, &catalogue, &mut campaign, &mut ui, &mut render) {
+            Ok(()) => {
+                board.set_cam(&mut cam);
+                game_state = GameState::Board;
+            },
+            Err(e) => ui.show_error(&e),
+        }
+    }
+
     let mut shop_btn = button::Button::new(
         GameObject::new_from_tex(render.texture_manager.load(
             Path::new("resources/textures/btn/shop.png")
@@ -51,13 +98,13 @@ pub fn main() -> Result<(), Error> {
     );
     let pm_tex = render.texture_manager.load(Path::new("resources/textures/btn/change.png"))?;
     let mut plus_btn = button::Button::new(
-        Tilemap::get_tile(pm_tex, 0, 0),
-        Tilemap::get_tile(pm_tex, 0, 1),
+        TileSprites::get_tile(pm_tex, 0, 0),
+        TileSprites::get_tile(pm_tex, 0, 1),
         Vec2::new(70.0, 10.0)
     );
     let mut minus_btn = button::Button::new(
-        Tilemap::get_tile(pm_tex, 1, 0),
-        Tilemap::get_tile(pm_tex, 1, 1),
+        TileSprites::get_tile(pm_tex, 1, 0),
+        TileSprites::get_tile(pm_tex, 1, 1),
         Vec2::new(70.0, 30.0)
     );
 
@@ -92,24 +139,54 @@ pub fn main() -> Result<(), Error> {
             }
         }
 
+        if game_state == GameState::Board && controls.kbm.press(Key::F5) {
+            if let Err(e) = board.save_game(&mut ui).write(Path::new(QUICK_SAVE)) {
+                ui.show_error(&e.into());
+            }
+        }
+        if controls.kbm.press(Key::F9) {
+            match quick_load(&mut board, &mut campaign, &mut ui, &mut render)
+                .and_then(|()| stock_shop(&mut shop, &catalogue, &mut board, &mut render)) {
+                Ok(()) => {
+                    game_state = GameState::Board;
+                    fade_time = 0.0;
+                    fade_in = true;
+                    fade_done = false;
+                    fade.colour.a = 0;
+                },
+                Err(e) => ui.show_error(&e),
+            }
+        }
+
         ui.update(&controls);
 
+        let mut next_level = None;
         match game_state {
+            GameState::LevelSelect => {
+                level_select.update(&controls, &campaign);
+                if let Some(i) = level_select.selected() {
+                    next_level = campaign.select(i).cloned();
+                }
+            },
             GameState::Board => {
-                board.update(&controls, &mut ui);
+                if let Err(e) = board.update(&controls, &mut ui) {
+                    ui.show_error(&e);
+                }
                 board.update_cam(&mut cam, &controls);
-                if board.lose {
+                if board.sim.lose {
                     game_state = GameState::Lose;
                     audio.sfx.play(lose_sfx)?;
                 }
-                if board.complete {
-                    ui.pop_tile();
+                if board.sim.complete {
+                    ui.clear_inventory();
                     game_state = GameState::Win;
                     audio.sfx.play(win_sfx)?;
                 }
             },
             GameState::Shop => {
-                shop.update(&controls, &mut ui);
+                if let Err(e) = shop.update(&controls, &mut ui) {
+                    ui.show_error(&e);
+                }
                 if shop.change() {
                     game_state = GameState::Board;
                 }
@@ -118,12 +195,17 @@ pub fn main() -> Result<(), Error> {
                 fade_time += controls.frame_elapsed;
                 fade.colour.a = ((fade_time  / DEATH_TIME) * 100.0) as u8;
                 if fade.colour.a > 200 {
-                    break;
+                    write_replay(&mut ui);
+                    game_state = GameState::LevelSelect;
                 }
             },
             GameState::Win => {
                 if controls.kbm.mouse_press(sdl_helper::input::keyboard::MouseButton::Left) {
-                    break;
+                    write_replay(&mut ui);
+                    match campaign.advance() {
+                        Some(level) => next_level = Some(level.clone()),
+                        None => game_state = GameState::LevelSelect,
+                    }
                 }
                 if !fade_done {
                     fade_time += controls.frame_elapsed;
@@ -132,7 +214,7 @@ pub fn main() -> Result<(), Error> {
                     } else {
                         fade.colour.a = ((1.0 -(fade_time  / WIN_FADE)) * 255.0) as u8;
                     }
-                    if (fade_in && fade.colour.a >= 255) ||
+                    if (fade_in && fade.colour.a == 255) ||
                     (!fade_in && fade.colour.a < 10) {
                         if !fade_in {
                             fade_done = true;
@@ -145,6 +227,21 @@ pub fn main() -> Result<(), Error> {
             }
         }
 
+        if let Some(level) = next_level {
+            game_state = match start_level(&level, &mut board, &mut ui, &mut cam, &mut render)
+                .and_then(|()| stock_shop(&mut shop, &catalogue, &mut board, &mut render)) {
+                Ok(()) => GameState::Board,
+                Err(e) => {
+                    ui.show_error(&e);
+                    GameState::LevelSelect
+                }
+            };
+            fade_time = 0.0;
+            fade_in = true;
+            fade_done = false;
+            fade.colour.a = 0;
+        }
+
         plus_btn.update(&controls);
         if controls.kbm.press(Key::Equals) || plus_btn.clicked() {
             increase_win_size(&mut cam, &mut render)?;
@@ -179,12 +276,66 @@ pub fn main() -> Result<(), Error> {
                 cam.draw(&win);
             }
         }
+
+        if game_state == GameState::LevelSelect {
+            level_select.draw(&mut cam);
+        }
         
         render.end_draw(&mut cam)?;
     }
+    write_replay(&mut ui);
+    Ok(())
+}
+
+fn start_level(level: &Level, board: &mut Board, ui: &mut Ui, cam: &mut Camera, render: &mut Render) -> Result<(), GameError> {
+    board.load_map(&level.map, render)?;
+    board.set_cam(cam);
+    ui.clear_inventory();
+    ui.set_money(level.money);
+    ui.set_purchases(Purchases::new());
+    ui.start_recording(&level.map);
+    Ok(())
+}
+
+fn start_replay(
+    path: &Path, board: &mut Board, shop: &mut Shop, catalogue: &Catalogue,
+    campaign: &mut Campaign, ui: &mut Ui, render: &mut Render,
+) -> Result<(), GameError> {
+    let replay = Replay::load(path)?;
+    board.load_map(&replay.map, render)?;
+    // purchases are checked against the level's own prices
+    stock_shop(shop, catalogue, board, render)?;
+    board.play_replay(&replay, shop, ui)?;
+    campaign.resume(&replay.map);
+    ui.resume_recording(replay);
     Ok(())
 }
 
+fn quick_load(board: &mut Board, campaign: &mut Campaign, ui: &mut Ui, render: &mut Render) -> Result<(), GameError> {
+    let save = SaveGame::load(Path::new(QUICK_SAVE))?;
+    let map = save.map.clone();
+    board.load_map(&map, render)?;
+    board.restore(save, ui)?;
+    campaign.resume(&map);
+    Ok(())
+}
+
+/// Fill the shop with what the level loaded into `board` sells.
+fn stock_shop(shop: &mut Shop, catalogue: &Catalogue, board: &mut Board, render: &mut Render) -> Result<(), GameError> {
+    let level = board.shop_catalogue(catalogue)?;
+    shop.set_catalogue(&level, render)?;
+    board.set_catalogue(level);
+    Ok(())
+}
+
+fn write_replay(ui: &mut Ui) {
+    if let Some(replay) = ui.stop_recording() {
+        if let Err(e) = replay.write(Path::new(LAST_REPLAY)) {
+            ui.show_error(&e.into());
+        }
+    }
+}
+
 
 fn increase_win_size(cam: &mut Camera, render: &mut Render) -> Result<(), Error> {
     let mut cs = cam.get_window_size();
@@ -0,0 +1,271 @@
+use serde::Serialize;
+
+/// What the level knows about a run, objectives are checked against this.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Progress {
+    /// turns finished
+    pub turns: usize,
+    /// carrots harvested since the map was loaded
+    pub harvested: usize,
+    pub money: usize,
+    /// carrots on the board
+    pub carrots: usize,
+    /// roots on the board
+    pub roots: usize,
+    /// a key has been placed on a door
+    pub door_opened: bool,
+    /// a turn has just finished
+    pub turn_ended: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Pending,
+    Met,
+    Failed,
+}
+
+/// Which objective a progress report is for, so the ui can pick an icon.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    OpenDoor,
+    Harvest,
+    Survive,
+    KeepCarrots,
+    ClearRoots,
+    Money,
+    TurnLimit,
+}
+
+/// How far along an objective is, for the ui.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ObjectiveProgress {
+    pub kind: Kind,
+    pub current: usize,
+    pub target: usize,
+    pub status: Status,
+}
+
+/// Something the level asks of the player. Goals must all be met to
+/// win, any objective that fails loses the level.
+pub trait Objective {
+    fn kind(&self) -> Kind;
+
+    /// true if this must be met to win, false if it can only be failed
+    fn is_goal(&self) -> bool {
+        true
+    }
+
+    fn status(&self, p: &Progress) -> Status;
+
+    /// current value and the value aimed for
+    fn progress(&self, p: &Progress) -> (usize, usize);
+}
+
+/// Put a key in a door.
+pub struct OpenDoor;
+
+impl Objective for OpenDoor {
+    fn kind(&self) -> Kind {
+        Kind::OpenDoor
+    }
+
+    fn status(&self, p: &Progress) -> Status {
+        if p.door_opened { Status::Met } else { Status::Pending }
+    }
+
+    fn progress(&self, p: &Progress) -> (usize, usize) {
+        (p.door_opened as usize, 1)
+    }
+}
+
+/// Harvest this many carrots.
+pub struct Harvest(pub usize);
+
+impl Objective for Harvest {
+    fn kind(&self) -> Kind {
+        Kind::Harvest
+    }
+
+    fn status(&self, p: &Progress) -> Status {
+        if p.harvested >= self.0 { Status::Met } else { Status::Pending }
+    }
+
+    fn progress(&self, p: &Progress) -> (usize, usize) {
+        (p.harvested.min(self.0), self.0)
+    }
+}
+
+/// Finish this many turns.
+pub struct Survive(pub usize);
+
+impl Objective for Survive {
+    fn kind(&self) -> Kind {
+        Kind::Survive
+    }
+
+    fn status(&self, p: &Progress) -> Status {
+        if p.turns >= self.0 { Status::Met } else { Status::Pending }
+    }
+
+    fn progress(&self, p: &Progress) -> (usize, usize) {
+        (p.turns.min(self.0), self.0)
+    }
+}
+
+/// End every turn with at least this many carrots on the board.
+pub struct KeepCarrots(pub usize);
+
+impl Objective for KeepCarrots {
+    fn kind(&self) -> Kind {
+        Kind::KeepCarrots
+    }
+
+    fn is_goal(&self) -> bool {
+        false
+    }
+
+    fn status(&self, p: &Progress) -> Status {
+        if p.turn_ended && p.carrots < self.0 { Status::Failed } else { Status::Pending }
+    }
+
+    fn progress(&self, p: &Progress) -> (usize, usize) {
+        (p.carrots, self.0)
+    }
+}
+
+/// Get rid of every root.
+pub struct ClearRoots;
+
+impl Objective for ClearRoots {
+    fn kind(&self) -> Kind {
+        Kind::ClearRoots
+    }
+
+    fn status(&self, p: &Progress) -> Status {
+        if p.roots == 0 { Status::Met } else { Status::Pending }
+    }
+
+    /// roots left, aiming for none
+    fn progress(&self, p: &Progress) -> (usize, usize) {
+        (p.roots, 0)
+    }
+}
+
+/// Have this much money at once.
+pub struct MoneyTarget(pub usize);
+
+impl Objective for MoneyTarget {
+    fn kind(&self) -> Kind {
+        Kind::Money
+    }
+
+    fn status(&self, p: &Progress) -> Status {
+        if p.money >= self.0 { Status::Met } else { Status::Pending }
+    }
+
+    fn progress(&self, p: &Progress) -> (usize, usize) {
+        (p.money.min(self.0), self.0)
+    }
+}
+
+/// Win before this many turns have finished.
+pub struct TurnLimit(pub usize);
+
+impl Objective for TurnLimit {
+    fn kind(&self) -> Kind {
+        Kind::TurnLimit
+    }
+
+    fn is_goal(&self) -> bool {
+        false
+    }
+
+    fn status(&self, p: &Progress) -> Status {
+        if p.turns >= self.0 { Status::Failed } else { Status::Pending }
+    }
+
+    fn progress(&self, p: &Progress) -> (usize, usize) {
+        (p.turns, self.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum Outcome {
+    Playing,
+    Won,
+    Lost,
+}
+
+/// The objectives of a level.
+pub struct Objectives {
+    list: Vec<Box<dyn Objective>>,
+}
+
+/// Open the door, and lose if a turn ends with no carrots.
+impl Default for Objectives {
+    fn default() -> Self {
+        Objectives {
+            list: vec![Box::new(OpenDoor), Box::new(KeepCarrots(1))],
+        }
+    }
+}
+
+impl Objectives {
+    pub fn new(list: Vec<Box<dyn Objective>>) -> Objectives {
+        Objectives { list }
+    }
+
+    /// Read objectives from a map's properties:
+    /// `harvest`, `survive`, `keep_carrots`, `money_target`, `turn_limit` (ints)
+    /// and `clear_roots`, `door` (bools).
+    /// Without any goals the door has to be opened, and without
+    /// `keep_carrots` a turn can't end with no carrots.
+    pub fn from_props(props: &tiled::Properties) -> Objectives {
+        let int = |name: &str| props.integers.get(name).map(|v| (*v).max(0) as usize);
+        let flag = |name: &str| props.booleans.get(name).copied().unwrap_or(false);
+        let mut list: Vec<Box<dyn Objective>> = Vec::new();
+        if let Some(n) = int("harvest") {
+            list.push(Box::new(Harvest(n)));
+        }
+        if let Some(n) = int("survive") {
+            list.push(Box::new(Survive(n)));
+        }
+        if flag("clear_roots") {
+            list.push(Box::new(ClearRoots));
+        }
+        if let Some(n) = int("money_target") {
+            list.push(Box::new(MoneyTarget(n)));
+        }
+        if flag("door") || list.is_empty() {
+            list.push(Box::new(OpenDoor));
+        }
+        match int("keep_carrots") {
+            Some(0) => (),
+            Some(n) => list.push(Box::new(KeepCarrots(n))),
+            None => list.push(Box::new(KeepCarrots(1))),
+        }
+        if let Some(n) = int("turn_limit") {
+            list.push(Box::new(TurnLimit(n)));
+        }
+        Objectives { list }
+    }
+
+    /// Won once every goal is met, lost if anything fails first.
+    pub fn check(&self, p: &Progress) -> Outcome {
+        if self.list.iter().filter(|o| o.is_goal()).all(|o| o.status(p) == Status::Met) {
+            return Outcome::Won;
+        }
+        if self.list.iter().any(|o| o.status(p) == Status::Failed) {
+            return Outcome::Lost;
+        }
+        Outcome::Playing
+    }
+
+    pub fn progress(&self, p: &Progress) -> Vec<ObjectiveProgress> {
+        self.list.iter().map(|o| {
+            let (current, target) = o.progress(p);
+            ObjectiveProgress { kind: o.kind(), current, target, status: o.status(p) }
+        }).collect()
+    }
+}
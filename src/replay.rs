@@ -0,0 +1,199 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Deserialize};
+
+use crate::catalogue::{Catalogue, Purchases};
+use crate::history::{History, Snapshot};
+use crate::inventory::Inventory;
+use crate::sim::Simulation;
+use crate::tiles::Tiles;
+
+pub const REPLAY_VERSION: u32 = 1;
+
+/// Something the player did that changes the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    /// tried to place a tile from the inventory, `step` is the simulation step it happened on
+    Place { tile: Tiles, x: usize, y: usize, step: usize },
+    Buy { tile: Tiles, cost: usize },
+    /// a placed tile was sold and turned back to Grass
+    Sell { x: usize, y: usize, step: usize, refund: usize },
+    /// the cell `to` was added to the patrol path of the goat at `(x, y)`
+    Waypoint { x: usize, y: usize, step: usize, to: (usize, usize) },
+    /// the patrol path of the goat at `(x, y)` was cleared
+    ClearPath { x: usize, y: usize, step: usize },
+    NextTurn,
+    Undo,
+    Redo,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Write(ron::Error),
+    Version(u32),
+    /// the action at this index can't happen on the replayed board
+    Desync(usize),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "could not access replay file: {}", e),
+            ReplayError::Parse(e) => write!(f, "could not parse replay file: {}", e),
+            ReplayError::Write(e) => write!(f, "could not write replay file: {}", e),
+            ReplayError::Version(v) => write!(
+                f, "replay file version {} is not supported, expected {}", v, REPLAY_VERSION),
+            ReplayError::Desync(i) => write!(f, "replay went out of sync at action {}", i),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Where the game ended up after playing a replay.
+#[derive(Clone, Debug)]
+pub struct ReplayOutcome {
+    pub money: usize,
+    pub inventory: Inventory,
+    pub purchases: Purchases,
+    pub turns: usize,
+    pub complete: bool,
+    pub lose: bool,
+}
+
+/// Every action taken since a map was loaded, enough to
+/// rebuild the same game from a fresh board.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub map: PathBuf,
+    /// money at the start of the run
+    pub money: usize,
+    pub actions: Vec<Action>,
+}
+
+impl Replay {
+    pub fn new(map: PathBuf, money: usize) -> Replay {
+        Replay { version: REPLAY_VERSION, map, money, actions: Vec::new() }
+    }
+
+    pub fn record(&mut self, action: Action) {
+        self.actions.push(action);
+    }
+
+    pub fn load(path: &Path) -> Result<Replay, ReplayError> {
+        let text = std::fs::read_to_string(path).map_err(ReplayError::Io)?;
+        let replay: Replay = ron::from_str(&text).map_err(ReplayError::Parse)?;
+        if replay.version != REPLAY_VERSION {
+            return Err(ReplayError::Version(replay.version));
+        }
+        Ok(replay)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), ReplayError> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(ReplayError::Write)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(ReplayError::Io)?;
+        }
+        std::fs::write(path, text).map_err(ReplayError::Io)
+    }
+
+    /// Run every action against `sim`, which should have just loaded `self.map`.
+    /// Purchases must cost what `catalogue` would have charged for them.
+    pub fn play(&self, sim: &mut Simulation, catalogue: &Catalogue)
+                -> Result<ReplayOutcome, ReplayError> {
+        let mut money = self.money;
+        let mut inventory = Inventory::new();
+        let mut purchases = Purchases::new();
+        let mut history = History::new();
+        for (i, a) in self.actions.iter().enumerate() {
+            purchases.set_turn(sim.turn_count());
+            let snapshot = Snapshot {
+                sim: sim.save_state(), money,
+                inventory: inventory.clone(), purchases: purchases.clone(),
+            };
+            match *a {
+                Action::Place { tile, x, y, step } => {
+                    if inventory.count(tile) == 0 || step != sim.step_count() ||
+                        x >= sim.board.w || y >= sim.board.h {
+                            return Err(ReplayError::Desync(i));
+                        }
+                    let placed = sim.place_tile(tile, x, y)
+                        .map_err(|_| ReplayError::Desync(i))?;
+                    if placed {
+                        inventory.take(tile);
+                    }
+                    sim.check(money);
+                    if placed || sim.complete {
+                        history.push(snapshot);
+                    }
+                },
+                Action::Buy { tile, cost } => {
+                    if catalogue.affordable(tile, money, &purchases) != Some(cost) {
+                        return Err(ReplayError::Desync(i));
+                    }
+                    money -= cost;
+                    inventory.add(tile);
+                    purchases.buy(tile);
+                },
+                Action::Sell { x, y, step, refund } => {
+                    if step != sim.step_count() {
+                        return Err(ReplayError::Desync(i));
+                    }
+                    let tile = sim.sell_tile(x, y).map_err(|_| ReplayError::Desync(i))?;
+                    if catalogue.sell_price(tile) != refund {
+                        return Err(ReplayError::Desync(i));
+                    }
+                    money += refund;
+                    history.push(snapshot);
+                },
+                Action::Waypoint { x, y, step, to } => {
+                    if step != sim.step_count() {
+                        return Err(ReplayError::Desync(i));
+                    }
+                    sim.add_waypoint(x, y, to).map_err(|_| ReplayError::Desync(i))?;
+                    history.push(snapshot);
+                },
+                Action::ClearPath { x, y, step } => {
+                    if step != sim.step_count() {
+                        return Err(ReplayError::Desync(i));
+                    }
+                    sim.set_path(x, y, Vec::new()).map_err(|_| ReplayError::Desync(i))?;
+                    history.push(snapshot);
+                },
+                Action::NextTurn => {
+                    history.push(snapshot);
+                    money += sim.advance_turn().harvested;
+                    sim.check(money);
+                },
+                Action::Undo | Action::Redo => {
+                    let s = if *a == Action::Undo {
+                        history.undo(snapshot)
+                    } else {
+                        history.redo(snapshot)
+                    };
+                    let s = match s {
+                        Some(s) => s,
+                        None => return Err(ReplayError::Desync(i)),
+                    };
+                    sim.restore(s.sim).map_err(|_| ReplayError::Desync(i))?;
+                    money = s.money;
+                    inventory = s.inventory;
+                    purchases = s.purchases;
+                },
+            }
+        }
+        Ok(ReplayOutcome {
+            money,
+            inventory,
+            purchases,
+            turns: sim.turn_count(),
+            complete: sim.complete,
+            lose: sim.lose,
+        })
+    }
+}
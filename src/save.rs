@@ -0,0 +1,73 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Deserialize};
+
+use crate::catalogue::Purchases;
+use crate::inventory::Inventory;
+use crate::sim::SimState;
+
+/// Bump this when `SaveGame` changes so old files are rejected instead of misread.
+pub const SAVE_VERSION: u32 = 8;
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Write(ron::Error),
+    Version(u32),
+    /// the saved board doesn't match its own size
+    Mismatch,
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "could not access save file: {}", e),
+            SaveError::Parse(e) => write!(f, "could not parse save file: {}", e),
+            SaveError::Write(e) => write!(f, "could not write save file: {}", e),
+            SaveError::Version(v) => write!(
+                f, "save file version {} is not supported, expected {}", v, SAVE_VERSION),
+            SaveError::Mismatch => write!(f, "save file board is corrupt"),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+/// The full state of a run, the board with every tile's counters,
+/// the player's money, the tiles they haven't placed and what they've bought.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub version: u32,
+    /// the map the run was started from
+    pub map: PathBuf,
+    pub money: usize,
+    pub inventory: Inventory,
+    pub sim: SimState,
+    pub purchases: Purchases,
+}
+
+impl SaveGame {
+    pub fn new(map: PathBuf, money: usize, inventory: Inventory, sim: SimState, purchases: Purchases) -> SaveGame {
+        SaveGame { version: SAVE_VERSION, map, money, inventory, sim, purchases }
+    }
+
+    pub fn load(path: &Path) -> Result<SaveGame, SaveError> {
+        let text = std::fs::read_to_string(path).map_err(SaveError::Io)?;
+        let save: SaveGame = ron::from_str(&text).map_err(SaveError::Parse)?;
+        if save.version != SAVE_VERSION {
+            return Err(SaveError::Version(save.version));
+        }
+        Ok(save)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), SaveError> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(SaveError::Write)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(SaveError::Io)?;
+        }
+        std::fs::write(path, text).map_err(SaveError::Io)
+    }
+}
@@ -1,42 +1,62 @@
-use std::{path::Path, collections::HashMap};
+use std::path::Path;
 
 use sdl_helper::Colour;
 use sdl_helper::geometry::Rect;
 use sdl_helper::{GameObject, Render, Error, Camera, input::Controls, geometry::Vec2};
+use crate::catalogue::Catalogue;
+use crate::error::GameError;
 use crate::tiles::Tiles;
 use crate::button::Button;
 use crate::ui::Ui;
+use crate::replay::Action;
 
 const OPEN_TIME : f64 = 0.8;
 const CLOSE_TIME: f64 = 0.4;
+/// where the first item's button goes
+const GRID_POS: Vec2 = Vec2::new(200.0, 100.0);
+/// distance between the buttons of the shop grid
+const GRID_STEP: Vec2 = Vec2::new(100.0, 50.0);
+/// where the last digit of an item's stock goes, relative to its button
+const STOCK_POS: Vec2 = Vec2::new(90.0, 10.0);
 
 struct ShopBtn {
     pub btn: Button,
-    pub cost: usize,
     pub t: Tiles,
-    pub set: bool,
+    /// the price and stock the digits were made for
+    shown: Option<(usize, Option<usize>)>,
     pub price_num: Vec<GameObject>,
     price_rects: Vec<Rect>,
     pub pos: Vec2,
 }
 
 impl ShopBtn {
-    pub fn new(t: Tiles, cost: usize, normal: GameObject, active: GameObject, pos: Vec2) -> Self {
+    pub fn new(t: Tiles, normal: GameObject, active: GameObject, pos: Vec2) -> Self {
         ShopBtn {
             btn: Button::new(normal, active, pos),
-            cost,
             t,
-            set: false,
+            shown: None,
             price_num: Vec::new(),
             price_rects: Vec::new(),
             pos,
         }
     }
 
-    pub fn set(&mut self, ui: &mut Ui) {
-        if self.set { return; }
-        self.set = true;
-        self.price_num = ui.get_nums(self.cost, self.pos + Vec2::new(70.0, 20.0));
+    /// Show `cost` beside the button, and the stock left above
+    /// it in small grey digits if there is a limit.
+    pub fn set(&mut self, cost: usize, stock: Option<usize>, ui: &mut Ui) {
+        if self.shown == Some((cost, stock)) { return; }
+        self.shown = Some((cost, stock));
+        self.price_num = ui.get_nums(cost, self.pos + Vec2::new(70.0, 20.0));
+        if let Some(stock) = stock {
+            for mut n in ui.get_nums(stock, Vec2::new(0.0, 0.0)) {
+                n.rect.x = self.pos.x + STOCK_POS.x + n.rect.x * 0.5;
+                n.rect.y = self.pos.y + STOCK_POS.y;
+                n.rect.w *= 0.5;
+                n.rect.h *= 0.5;
+                n.colour = Colour::new(150, 150, 150, 255);
+                self.price_num.push(n);
+            }
+        }
         self.price_rects.clear();
         for p in self.price_num.iter() {
             self.price_rects.push(p.rect);
@@ -62,11 +82,11 @@ pub struct Shop {
     exit_og: Rect,
     close_time: f64,
     closed: bool,
-    first: bool,
+    catalogue: Catalogue,
 }
 
 impl Shop {
-    pub fn new(render: &mut Render) -> Result<Shop, Error> {
+    pub fn new(render: &mut Render, catalogue: &Catalogue) -> Result<Shop, Error> {
         let mut fade = GameObject::new_from_tex(
             render.texture_manager.load(
                 Path::new("resources/textures/tiles/blank.png"))?);
@@ -102,55 +122,13 @@ impl Shop {
         );
         
         shopkeep.parallax = Vec2::new(0.0, 0.0);
-        let base_btn = Vec2::new(200.0, 100.0);
         Ok(Shop {
             since_opened: 0.0,
             bg,
             fade,
-            first: true,
             shopkeep,
-            tile_btns: vec![
-                ShopBtn::new(Tiles::Goat, 20, GameObject::new_from_tex(
-                             render.texture_manager.load(
-                                 Path::new("resources/textures/btn/goat.png"))?),
-                             GameObject::new_from_tex(
-                                 render.texture_manager.load(
-                                     Path::new("resources/textures/btn/goat_active.png"))?),
-                             base_btn,
-                ),
-                ShopBtn::new(Tiles::Bush, 40, GameObject::new_from_tex(
-                             render.texture_manager.load(
-                                 Path::new("resources/textures/btn/bush.png"))?),
-                             GameObject::new_from_tex(
-                                 render.texture_manager.load(
-                                     Path::new("resources/textures/btn/bush_active.png"))?),
-                             base_btn + Vec2::new(0.0, 50.0)
-                ),
-                ShopBtn::new(Tiles::Water, 120, GameObject::new_from_tex(
-                             render.texture_manager.load(
-                                 Path::new("resources/textures/btn/water.png"))?),
-                             GameObject::new_from_tex(
-                                 render.texture_manager.load(
-                                     Path::new("resources/textures/btn/water_active.png"))?),
-                             base_btn + Vec2::new(100.0, 0.0)
-                ),
-                ShopBtn::new(Tiles::Ice, 70, GameObject::new_from_tex(
-                             render.texture_manager.load(
-                                 Path::new("resources/textures/btn/ice.png"))?),
-                             GameObject::new_from_tex(
-                                 render.texture_manager.load(
-                                     Path::new("resources/textures/btn/ice_active.png"))?),
-                             base_btn + Vec2::new(100.0, 50.0)
-                ),
-                ShopBtn::new(Tiles::Key, 1000, GameObject::new_from_tex(
-                             render.texture_manager.load(
-                                 Path::new("resources/textures/btn/key.png"))?),
-                             GameObject::new_from_tex(
-                                 render.texture_manager.load(
-                                     Path::new("resources/textures/btn/key_active.png"))?),
-                             base_btn + Vec2::new(50.0, 120.0)
-                ),
-            ],
+            tile_btns: shop_btns(catalogue, render)?,
+            catalogue: catalogue.clone(),
             exit,
             exit_og,
             closed: false,
@@ -158,6 +136,13 @@ impl Shop {
         })
     }
 
+    /// Sell the items of `catalogue` instead.
+    pub fn set_catalogue(&mut self, catalogue: &Catalogue, render: &mut Render) -> Result<(), Error> {
+        self.tile_btns = shop_btns(catalogue, render)?;
+        self.catalogue = catalogue.clone();
+        Ok(())
+    }
+
     fn set_game_state(&mut self, factor: f64) {
         let change = -100.0 * factor;
         self.shopkeep.rect.x = change;
@@ -169,25 +154,21 @@ impl Shop {
         self.fade.colour.a = ((1.0 - factor) * 100.0) as u8;
     }
 
+    /// What the shop is selling and for how much.
+    pub fn catalogue(&self) -> &Catalogue {
+        &self.catalogue
+    }
+
     pub fn close_shop(&mut self) {
         self.closed = true;
     }
 
-    pub fn update(&mut self, input: &Controls, ui: &mut Ui) {
-        if self.first {
-            self.first = false;
-            for btn in self.tile_btns.iter_mut() {
-                if !btn.set {
-                    btn.set(ui);
-                }
-            }
-        }
-        
+    pub fn update(&mut self, input: &Controls, ui: &mut Ui) -> Result<(), GameError> {
         if self.closed {
             self.close_time += input.frame_elapsed;
             let close_ratio = (self.close_time / CLOSE_TIME).powf(2.0);
             self.set_game_state(close_ratio);
-            return;
+            return Ok(());
         }
         
         self.since_opened += input.frame_elapsed;
@@ -195,33 +176,29 @@ impl Shop {
             let open_ratio = 1.0 - (self.since_opened / OPEN_TIME).powf(0.5);
             self.set_game_state(open_ratio);
         }
-        let t = ui.pop_tile();
-        if t != Tiles::None {
-            for b in self.tile_btns.iter() {
-                if b.t == t {
-                    ui.set_money(ui.get_money() + b.cost);
-                }
-            }
-        }
         for btn in self.tile_btns.iter_mut() {
+            let cost = self.catalogue.price(btn.t, ui.purchases()).unwrap_or(0);
+            let stock = self.catalogue.stock_left(btn.t, ui.purchases());
+            btn.set(cost, stock, ui);
             btn.btn.update(input);
-            btn.btn.set_colour(if  ui.get_money() < btn.cost {
+            let can_buy = ui.get_money() >= cost && stock != Some(0);
+            btn.btn.set_colour(if !can_buy {
                 Colour::new(150, 150, 150, 255)
             } else {
                 Colour::white()
             });
-            if btn.btn.clicked() {
-                if ui.get_money() >= btn.cost {
-                    ui.set_money(ui.get_money() - btn.cost);
-                    ui.set_tile(btn.t);
-                    self.closed = true;
-                }
+            if btn.btn.clicked() && can_buy {
+                ui.add_tile(btn.t)?;
+                ui.set_money(ui.get_money() - cost);
+                ui.purchases_mut().buy(btn.t);
+                ui.record(Action::Buy { tile: btn.t, cost });
             }
         }
         self.exit.update(input);
         if self.exit.clicked() {
             self.closed = true;
         }
+        Ok(())
     }
 
     pub fn draw(&self, cam: &mut Camera) {
@@ -247,3 +224,29 @@ impl Shop {
         self.closed = false;
     }
 }
+
+/// A button for each item in `catalogue`, laid out in rows of `columns`
+/// with a final row that isn't full centred under the others.
+fn shop_btns(catalogue: &Catalogue, render: &mut Render) -> Result<Vec<ShopBtn>, Error> {
+    let columns = catalogue.columns.max(1);
+    let last_row = catalogue.items.len() / columns;
+    let last_row_len = catalogue.items.len() % columns;
+    let mut btns = Vec::with_capacity(catalogue.items.len());
+    for (i, item) in catalogue.items.iter().enumerate() {
+        let (col, row) = (i % columns, i / columns);
+        let mut offset = 0.0;
+        if row == last_row {
+            offset = (columns - last_row_len) as f64 * GRID_STEP.x / 2.0;
+        }
+        let pos = GRID_POS + Vec2::new(
+            col as f64 * GRID_STEP.x + offset,
+            row as f64 * GRID_STEP.y);
+        btns.push(ShopBtn::new(
+            item.tile,
+            GameObject::new_from_tex(render.texture_manager.load(&item.icon)?),
+            GameObject::new_from_tex(render.texture_manager.load(&item.icon_active)?),
+            pos,
+        ));
+    }
+    Ok(btns)
+}
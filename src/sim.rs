@@ -0,0 +1,667 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use serde::{Serialize, Deserialize};
+
+use crate::error::GameError;
+use crate::objective::{Objectives, ObjectiveProgress, Outcome, Progress};
+use crate::save::SaveError;
+use crate::tiles::{Tiles, Tilemap, Tile, Choice, Move, Resolver, Rules, TileState, MapSnapshot, Structure, TileTypes};
+
+pub const STEPS_PER_TURN : usize = 6;
+
+/// A cell whose tile was replaced during a step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellChange {
+    pub x: usize,
+    pub y: usize,
+    pub from: Tiles,
+    pub to: Tiles,
+}
+
+/// Who put the tile in a cell there, which decides if the player can sell it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Owner {
+    /// part of the map, or grown there during play
+    #[default]
+    Map,
+    /// part of the map, with the `sellable` property set in Tiled
+    Sellable,
+    /// placed by the player
+    Player,
+}
+
+/// What happened during a single step.
+#[derive(Clone, Debug, Default)]
+pub struct StepReport {
+    pub step: usize,
+    pub changes: Vec<CellChange>,
+    pub harvested: usize,
+    pub turn_over: bool,
+}
+
+/// What happened during the steps of a turn.
+#[derive(Clone, Debug, Default)]
+pub struct TurnReport {
+    pub turn: usize,
+    pub steps: Vec<StepReport>,
+    pub harvested: usize,
+}
+
+/// Everything needed to restore a `Simulation` exactly.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SimState {
+    pub w: usize,
+    pub h: usize,
+    pub map: Vec<Tiles>,
+    pub tiles: Vec<TileState>,
+    pub ages: Vec<usize>,
+    pub owners: Vec<Owner>,
+    pub structures: Vec<Structure>,
+    pub steps_left: usize,
+    pub step_count: usize,
+    pub turn_count: usize,
+    pub complete: bool,
+    pub lose: bool,
+    pub harvested: usize,
+    pub door_opened: bool,
+}
+
+/// The game rules without any rendering, the grid, its tile objects
+/// and the step function. Can run with no window or textures.
+///
+/// Stepping only depends on the board and the placements made,
+/// so the same inputs always produce the same board.
+pub struct Simulation {
+    pub board: Tilemap,
+    obj_map: Vec<Box<dyn Tile>>,
+    /// steps each tile object has been alive for
+    ages: Vec<usize>,
+    /// who put each tile object there
+    owners: Vec<Owner>,
+    pub resolver: Resolver,
+    /// steps in each turn, `STEPS_PER_TURN` unless changed
+    steps_per_turn: usize,
+    steps_left: usize,
+    step_count: usize,
+    turn_count: usize,
+    /// carrots harvested since the map was loaded
+    harvested: usize,
+    door_opened: bool,
+    /// the last step finished a turn, nothing has been placed since
+    turn_ended: bool,
+    pub objectives: Objectives,
+    pub complete: bool,
+    pub lose: bool,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Simulation::new()
+    }
+}
+
+impl Simulation {
+    pub fn new() -> Simulation {
+        Simulation {
+            board: Tilemap::new(),
+            obj_map: Vec::new(),
+            ages: Vec::new(),
+            owners: Vec::new(),
+            resolver: Resolver::default(),
+            steps_per_turn: STEPS_PER_TURN,
+            steps_left: 0,
+            step_count: 0,
+            turn_count: 0,
+            harvested: 0,
+            door_opened: false,
+            turn_ended: false,
+            objectives: Objectives::default(),
+            complete: false,
+            lose: false,
+        }
+    }
+
+    /// Rules used by tile objects created after this is called,
+    /// so set these before loading a map.
+    pub fn set_rules(&mut self, rules: Rules) {
+        self.board.rules = rules;
+    }
+
+    /// Change how many steps a turn takes, from the next turn started.
+    pub fn set_steps_per_turn(&mut self, steps: usize) {
+        self.steps_per_turn = steps.max(1);
+    }
+
+    /// Read a `.tmx` file and start a fresh run on it.
+    pub fn load_file(&mut self, path: &Path) -> Result<(), GameError> {
+        let map = tiled::Map::new(path)
+            .map_err(|e| GameError::Map(format!("{}: {:?}", path.display(), e)))?;
+        self.load_map(&map, &TileTypes::load(path)?)
+    }
+
+    /// Start a fresh run on `map`, nothing changes if the map can't be read.
+    pub fn load_map(&mut self, map: &tiled::Map, types: &TileTypes) -> Result<(), GameError> {
+        self.board.set_map(map, types)?;
+        self.board.set_tile_objs(&mut self.obj_map);
+        self.ages.clear();
+        self.ages.resize(self.obj_map.len(), 0);
+        self.owners = (0..self.obj_map.len()).map(|i| {
+            match self.board.init.get(&i).and_then(|p| p.flag("sellable")) {
+                Some(true) => Owner::Sellable,
+                _ => Owner::Map,
+            }
+        }).collect();
+        self.steps_left = 0;
+        self.step_count = 0;
+        self.turn_count = 0;
+        self.harvested = 0;
+        self.door_opened = false;
+        self.turn_ended = false;
+        self.objectives = Objectives::from_props(&map.props);
+        self.complete = false;
+        self.lose = false;
+        Ok(())
+    }
+
+    /// Try to place `tile` at `(x, y)`, returns true if the tile was used up.
+    /// Placing a Key on a Door opens it, a structure goes down with its top
+    /// left cell at `(x, y)` if all of it fits.
+    pub fn place_tile(&mut self, tile: Tiles, x: usize, y: usize) -> Result<bool, GameError> {
+        let i = self.board.bi(x, y)?;
+        let prev_tile = self.board.map[i];
+
+        self.turn_ended = false;
+        if tile == Tiles::Key {
+            if prev_tile == Tiles::Door {
+                self.door_opened = true;
+            }
+            return Ok(false);
+        }
+        if self.board.rules.get(tile).footprint().is_some() {
+            return Ok(self.board.build(tile, x, y, Owner::Player));
+        }
+
+        if prev_tile != Tiles::None && prev_tile != Tiles::Door {
+            self.set(Choice { i, x, y, src: Tiles::None, dst: tile }, &mut Vec::new());
+            self.owners[i] = Owner::Player;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// True if the tile at `(x, y)` can be given a path to follow.
+    pub fn can_patrol(&self, x: usize, y: usize) -> bool {
+        match self.board.bi(x, y) {
+            Ok(i) => self.obj_map[i].movable(),
+            Err(_) => false,
+        }
+    }
+
+    /// Cells the tile at `(x, y)` visits in order.
+    pub fn path(&self, x: usize, y: usize) -> Result<&[(usize, usize)], GameError> {
+        Ok(self.obj_map[self.board.bi(x, y)?].path())
+    }
+
+    /// Have the tile at `(x, y)` visit the cells of `path` in order, over and over.
+    pub fn set_path(&mut self, x: usize, y: usize, path: Vec<(usize, usize)>) -> Result<(), GameError> {
+        let i = self.board.bi(x, y)?;
+        if let Some((px, py)) = path.iter().find(|(px, py)| self.board.bi(*px, *py).is_err()) {
+            return Err(GameError::OutOfRange { x: *px, y: *py });
+        }
+        if !self.obj_map[i].movable() {
+            return Err(GameError::NotMovable { x, y });
+        }
+        self.turn_ended = false;
+        self.obj_map[i].set_path(path);
+        Ok(())
+    }
+
+    /// Add `to` to the end of the path of the tile at `(x, y)`.
+    pub fn add_waypoint(&mut self, x: usize, y: usize, to: (usize, usize)) -> Result<(), GameError> {
+        let mut path = self.path(x, y)?.to_vec();
+        path.push(to);
+        self.set_path(x, y, path)
+    }
+
+    /// True if the player can sell the tile at `(x, y)`, only their own
+    /// tiles and ones the map marks as `sellable` can be sold. A structure is
+    /// sold from its top left cell, and the tiles under it can't be sold.
+    pub fn can_sell(&self, x: usize, y: usize) -> bool {
+        let i = match self.board.bi(x, y) {
+            Ok(i) => i,
+            Err(_) => return false,
+        };
+        if let Some(s) = self.board.structure_at(x, y) {
+            return s.anchored_at(x, y) && s.owner != Owner::Map;
+        }
+        let tile = self.board.map[i];
+        self.owners[i] != Owner::Map &&
+            tile != Tiles::Grass && tile != Tiles::None && tile != Tiles::Door
+    }
+
+    /// Turn the tile at `(x, y)` back to Grass, or take away the structure
+    /// built from it, returns the tile that was sold.
+    pub fn sell_tile(&mut self, x: usize, y: usize) -> Result<Tiles, GameError> {
+        let i = self.board.bi(x, y)?;
+        if !self.can_sell(x, y) {
+            return Err(GameError::NotSellable { x, y });
+        }
+        self.turn_ended = false;
+        if let Some(tile) = self.board.demolish(x, y) {
+            return Ok(tile);
+        }
+        let tile = self.board.map[i];
+        self.set(Choice { i, x, y, src: Tiles::None, dst: Tiles::Grass }, &mut Vec::new());
+        Ok(tile)
+    }
+
+    /// Start a turn, does nothing if one is already running.
+    pub fn start_turn(&mut self) {
+        if self.steps_left == 0 {
+            self.steps_left = self.steps_per_turn;
+            self.turn_ended = false;
+        }
+    }
+
+    pub fn in_turn(&self) -> bool {
+        self.steps_left > 0
+    }
+
+    pub fn steps_left(&self) -> usize {
+        self.steps_left
+    }
+
+    /// Total steps run since the map was loaded.
+    pub fn step_count(&self) -> usize {
+        self.step_count
+    }
+
+    /// Turns finished since the map was loaded.
+    pub fn turn_count(&self) -> usize {
+        self.turn_count
+    }
+
+    /// Run the next step of the current turn, starting a turn if none is running.
+    /// Call `check` afterwards to see if the level was won or lost.
+    pub fn advance_step(&mut self) -> StepReport {
+        self.start_turn();
+        let mut report = self.step();
+        self.harvested += report.harvested;
+        self.steps_left -= 1;
+        if self.steps_left == 0 {
+            self.turn_count += 1;
+            self.turn_ended = true;
+            report.turn_over = true;
+        }
+        report
+    }
+
+    /// Run the remaining steps of the current turn,
+    /// or a whole turn if none is running.
+    pub fn advance_turn(&mut self) -> TurnReport {
+        let mut report = TurnReport { turn: self.turn_count, ..Default::default() };
+        self.start_turn();
+        while self.steps_left > 0 {
+            let s = self.advance_step();
+            report.harvested += s.harvested;
+            report.steps.push(s);
+        }
+        report
+    }
+
+    pub fn progress(&self, money: usize) -> Progress {
+        Progress {
+            turns: self.turn_count,
+            harvested: self.harvested,
+            money,
+            carrots: self.board.map.iter().filter(|t| **t == Tiles::Carrot).count(),
+            roots: self.board.map.iter().filter(|t| **t == Tiles::Root).count(),
+            door_opened: self.door_opened,
+            turn_ended: self.turn_ended,
+        }
+    }
+
+    /// Check the level's objectives with the player holding `money`,
+    /// setting `complete` or `lose`. Once either is set it stays set.
+    pub fn check(&mut self, money: usize) -> Outcome {
+        if !self.complete && !self.lose {
+            match self.objectives.check(&self.progress(money)) {
+                Outcome::Won => self.complete = true,
+                Outcome::Lost => self.lose = true,
+                Outcome::Playing => (),
+            }
+        }
+        if self.complete {
+            Outcome::Won
+        } else if self.lose {
+            Outcome::Lost
+        } else {
+            Outcome::Playing
+        }
+    }
+
+    pub fn objective_progress(&self, money: usize) -> Vec<ObjectiveProgress> {
+        self.objectives.progress(&self.progress(money))
+    }
+
+    /// Run every tile object once and apply the changes they asked for.
+    /// Nothing is applied until every tile has updated, so each one sees
+    /// the board as it was at the start of the step.
+    fn step(&mut self) -> StepReport {
+        let mut changes = Vec::new();
+        for i in 0..self.obj_map.len() {
+            self.obj_map[i].update(&mut self.board);
+            self.ages[i] += 1;
+            let (x, y) = self.obj_map[i].pos();
+            let rule = self.board.rules.get(self.obj_map[i].tile());
+            let age = self.obj_map[i].age().unwrap_or(self.ages[i]);
+            if self.obj_map[i].removed() {
+                self.board.map_updates.push(Choice { i, x, y, src: Tiles::None, dst: Tiles::Grass });
+            } else if rule.lifetime.is_some_and(|l| age >= l) {
+                self.board.map_updates.push(Choice { i, x, y, src: Tiles::None, dst: rule.remains });
+            }
+        }
+        // tiles leaving their cell go first, then the moves, and the rest
+        // are resolved against whatever is in the cell after that
+        let (leaving, rest): (Vec<Choice>, Vec<Choice>) =
+            self.board.map_updates.drain(..).partition(|c| c.src == Tiles::None);
+        let left: HashSet<usize> = leaving.iter().map(|c| c.i).collect();
+        for c in leaving {
+            self.set(c, &mut changes);
+        }
+        let moves: Vec<Move> = self.board.moves.drain(..).collect();
+        for m in Self::unblocked(&moves, &left) {
+            self.move_tile(m, &mut changes);
+        }
+        let mut cells: BTreeMap<usize, Vec<Choice>> = BTreeMap::new();
+        for c in rest {
+            cells.entry(c.i).or_default().push(c);
+        }
+        for (i, choices) in cells {
+            let occupant = self.obj_map[i].tile();
+            for c in self.resolver.resolve(occupant, choices) {
+                self.apply(c, &mut changes);
+            }
+        }
+        let harvested = self.board.harvestable.len();
+        self.board.harvestable.clear();
+        let report = StepReport {
+            step: self.step_count,
+            changes,
+            harvested,
+            ..Default::default()
+        };
+        self.step_count += 1;
+        report
+    }
+
+    /// The moves that can all be made at once. A move is blocked if either cell
+    /// was left this step, if another tile is moving into the same cell or if the
+    /// tile in the cell it is moving into is moving too, so no move depends on
+    /// which tile asked first.
+    fn unblocked(moves: &[Move], left: &HashSet<usize>) -> Vec<Move> {
+        let mut into: HashMap<usize, usize> = HashMap::new();
+        for m in moves.iter() {
+            *into.entry(m.to).or_default() += 1;
+        }
+        let from: HashSet<usize> = moves.iter().map(|m| m.from).collect();
+        moves.iter().copied().filter(|m| {
+            m.from != m.to && into[&m.to] == 1 && !from.contains(&m.to) &&
+                !left.contains(&m.from) && !left.contains(&m.to)
+        }).collect()
+    }
+
+    /// Move a tile object to another cell, leaving Grass behind.
+    fn move_tile(&mut self, m: Move, changes: &mut Vec<CellChange>) {
+        if self.board.map[m.from] != m.src || self.board.map[m.to] != m.onto {
+            return;
+        }
+        let (fx, fy) = self.obj_map[m.from].pos();
+        let (tx, ty) = self.obj_map[m.to].pos();
+        if self.board.blocks(m.src, tx, ty) {
+            return;
+        }
+        let mut obj = std::mem::replace(
+            &mut self.obj_map[m.from], self.board.set_tile_obj(Tiles::Grass, fx, fy));
+        obj.set_pos(tx, ty);
+        self.obj_map[m.to] = obj;
+        changes.push(CellChange { x: fx, y: fy, from: m.src, to: Tiles::Grass });
+        changes.push(CellChange { x: tx, y: ty, from: m.onto, to: m.src });
+        self.board.map[m.from] = Tiles::Grass;
+        self.board.map[m.to] = m.src;
+        self.ages[m.to] = self.ages[m.from];
+        self.ages[m.from] = 0;
+        self.owners[m.to] = self.owners[m.from];
+        self.owners[m.from] = Owner::Map;
+    }
+
+    fn apply(&mut self, c: Choice, changes: &mut Vec<CellChange>) {
+        let current_tile = self.obj_map[c.i].tile();
+        if current_tile != c.dst {
+            if current_tile == Tiles::Grass && c.dst != Tiles::Ice {
+                self.set(c, changes);
+            } else  {
+                self.obj_map[c.i].interact(c.dst);
+                if self.obj_map[c.i].removed() {
+                    let dst = self.board.rules.get(current_tile).leaves(c.dst);
+                    self.set(Choice { dst, ..c }, changes);
+                }
+            }
+        }
+    }
+
+    pub fn save_state(&self) -> SimState {
+        SimState {
+            w: self.board.w,
+            h: self.board.h,
+            map: self.board.map.clone(),
+            tiles: self.obj_map.iter().map(|t| t.save()).collect(),
+            ages: self.ages.clone(),
+            owners: self.owners.clone(),
+            structures: self.board.structures().to_vec(),
+            steps_left: self.steps_left,
+            step_count: self.step_count,
+            turn_count: self.turn_count,
+            complete: self.complete,
+            lose: self.lose,
+            harvested: self.harvested,
+            door_opened: self.door_opened,
+        }
+    }
+
+    /// Replace the board and every tile object with a saved state.
+    pub fn restore(&mut self, state: SimState) -> Result<(), SaveError> {
+        let size = state.w * state.h;
+        if state.map.len() != size || state.tiles.len() != size || state.ages.len() != size
+            || state.owners.len() != size {
+            return Err(SaveError::Mismatch);
+        }
+        self.board.w = state.w;
+        self.board.h = state.h;
+        self.board.map = state.map;
+        self.board.map_updates.clear();
+        self.board.harvestable.clear();
+        self.obj_map = state.tiles.into_iter()
+            .map(|t| t.into_tile(&self.board.rules)).collect();
+        self.ages = state.ages;
+        self.owners = state.owners;
+        self.board.set_structures(state.structures);
+        self.steps_left = state.steps_left;
+        self.step_count = state.step_count;
+        self.turn_count = state.turn_count;
+        self.complete = state.complete;
+        self.lose = state.lose;
+        self.harvested = state.harvested;
+        self.door_opened = state.door_opened;
+        self.turn_ended = false;
+        Ok(())
+    }
+
+    pub fn snapshot(&self) -> MapSnapshot {
+        self.board.snapshot(&self.obj_map)
+    }
+
+    pub fn has_carrots(&self) -> bool {
+        self.board.map.contains(&Tiles::Carrot)
+    }
+
+    pub fn tile_objs(&self) -> &[Box<dyn Tile>] {
+        &self.obj_map
+    }
+
+    fn set(&mut self, c: Choice, changes: &mut Vec<CellChange>) {
+        changes.push(CellChange {
+            x: c.x, y: c.y,
+            from: self.obj_map[c.i].tile(),
+            to: c.dst,
+        });
+        self.obj_map[c.i] = self.board.set_tile_obj(c.dst, c.x, c.y);
+        self.board.map[c.i] = c.dst;
+        self.ages[c.i] = 0;
+        self.owners[c.i] = Owner::Map;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tiles::TileProps;
+    use crate::tiles::types::Root;
+
+    /// A fresh run on a `w` wide board of `tiles`, with the default rules.
+    fn sim(w: usize, tiles: Vec<Tiles>) -> Simulation {
+        let mut sim = Simulation::new();
+        sim.board.h = tiles.len() / w;
+        sim.board.w = w;
+        sim.board.map = tiles;
+        sim.board.set_tile_objs(&mut sim.obj_map);
+        sim.ages = vec![0; sim.obj_map.len()];
+        sim.owners = vec![Owner::Map; sim.obj_map.len()];
+        sim
+    }
+
+    /// Make the Root at `(x, y)` fully grown so it spreads on its next growth.
+    fn mature(sim: &mut Simulation, x: usize, y: usize) {
+        let mut root = Root::new(x, y, sim.board.rules.get(Tiles::Root));
+        root.age = root.max_age;
+        sim.obj_map[y * sim.board.w + x] = Box::new(root);
+    }
+
+    #[test]
+    fn advance_turn_spreads_roots() {
+        let mut sim = sim(3, vec![
+            Tiles::Grass, Tiles::Grass, Tiles::Grass,
+            Tiles::Grass, Tiles::Root, Tiles::Grass,
+            Tiles::Grass, Tiles::Grass, Tiles::Grass,
+        ]);
+        mature(&mut sim, 1, 1);
+        let report = sim.advance_turn();
+        assert_eq!(report.turn, 0);
+        assert_eq!(report.steps.len(), STEPS_PER_TURN);
+        assert!(report.steps.last().unwrap().turn_over);
+        assert_eq!(sim.turn_count(), 1);
+        assert!(!sim.in_turn());
+        for (x, y) in [(1, 0), (0, 1), (2, 1), (1, 2)] {
+            assert_eq!(sim.board.get(x, y), Tiles::Root, "({}, {})", x, y);
+        }
+        for (x, y) in [(0, 0), (2, 0), (0, 2), (2, 2)] {
+            assert_eq!(sim.board.get(x, y), Tiles::Grass, "({}, {})", x, y);
+        }
+        let changes: usize = report.steps.iter().map(|s| s.changes.len()).sum();
+        assert_eq!(changes, 4);
+    }
+
+    /// Mirroring a row reverses the order its tiles are updated in,
+    /// so the outcome has to be the same row mirrored.
+    #[test]
+    fn update_order_does_not_change_outcome() {
+        let row = vec![
+            Tiles::Root, Tiles::Grass, Tiles::Bush, Tiles::Grass,
+            Tiles::Carrot, Tiles::Root, Tiles::Grass, Tiles::Water,
+        ];
+        let mut forward = sim(row.len(), row.clone());
+        let mut backward = sim(row.len(), row.iter().rev().copied().collect());
+        for (x, _) in row.iter().enumerate().filter(|(_, t)| **t == Tiles::Root) {
+            mature(&mut forward, x, 0);
+            mature(&mut backward, row.len() - 1 - x, 0);
+        }
+        for _ in 0..3 {
+            forward.advance_turn();
+            backward.advance_turn();
+            let mirrored: Vec<Tiles> = backward.board.map.iter().rev().copied().collect();
+            assert_eq!(forward.board.map, mirrored);
+        }
+        assert_ne!(forward.board.map, row);
+    }
+
+    #[test]
+    fn structure_is_sold_from_its_top_left_cell() {
+        let mut sim = sim(3, vec![Tiles::Grass; 3]);
+        assert!(sim.place_tile(Tiles::Fence, 1, 0).unwrap());
+        assert!(!sim.can_sell(2, 0));
+        assert!(sim.sell_tile(2, 0).is_err());
+        assert!(sim.can_sell(1, 0));
+        assert_eq!(sim.sell_tile(1, 0).unwrap(), Tiles::Fence);
+        assert!(sim.board.structures().is_empty());
+    }
+
+    /// A `w` wide board of `tiles` whose goats take a step every step.
+    fn walking(w: usize, tiles: Vec<Tiles>) -> Simulation {
+        let mut walk = TileProps::new();
+        walk.set("move_period", 1);
+        let mut sim = sim(w, tiles);
+        for (i, t) in sim.board.map.iter().enumerate() {
+            if *t == Tiles::Goat {
+                sim.board.init.insert(i, walk.clone());
+            }
+        }
+        sim.board.set_tile_objs(&mut sim.obj_map);
+        sim
+    }
+
+    #[test]
+    fn goats_moving_into_the_same_cell_both_stay() {
+        let row = vec![Tiles::Goat, Tiles::Grass, Tiles::Goat];
+        let mut sim = walking(3, row.clone());
+        sim.set_path(0, 0, vec![(1, 0)]).unwrap();
+        sim.set_path(2, 0, vec![(1, 0)]).unwrap();
+        sim.start_turn();
+        sim.advance_step();
+        assert_eq!(sim.board.map, row);
+
+        let mut sim = walking(3, row);
+        sim.set_path(0, 0, vec![(1, 0)]).unwrap();
+        sim.start_turn();
+        sim.advance_step();
+        assert_eq!(sim.board.map, vec![Tiles::Grass, Tiles::Goat, Tiles::Goat]);
+    }
+
+    /// Goats heading for the same cell past roots and a bush end up
+    /// the same whichever way round the row is updated.
+    #[test]
+    fn update_order_does_not_change_moves() {
+        let row = vec![
+            Tiles::Goat, Tiles::Grass, Tiles::Root, Tiles::Grass,
+            Tiles::Root, Tiles::Grass, Tiles::Goat, Tiles::Bush, Tiles::Grass,
+        ];
+        let end = row.len() - 1;
+        let mut forward = walking(row.len(), row.clone());
+        let mut backward = walking(row.len(), row.iter().rev().copied().collect());
+        for (x, _) in row.iter().enumerate().filter(|(_, t)| **t == Tiles::Root) {
+            mature(&mut forward, x, 0);
+            mature(&mut backward, end - x, 0);
+        }
+        for (from, to) in [(0, 3), (6, 3)] {
+            forward.set_path(from, 0, vec![(to, 0)]).unwrap();
+            backward.set_path(end - from, 0, vec![(end - to, 0)]).unwrap();
+        }
+        for _ in 0..3 {
+            forward.advance_turn();
+            backward.advance_turn();
+            let mirrored: Vec<Tiles> = backward.board.map.iter().rev().copied().collect();
+            assert_eq!(forward.board.map, mirrored);
+        }
+        assert_ne!(forward.board.map, row);
+    }
+}
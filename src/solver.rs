@@ -0,0 +1,226 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use crate::catalogue::{Catalogue, Purchases};
+use crate::objective::Outcome;
+use crate::replay::{Action, Replay};
+use crate::sim::{Simulation, SimState};
+use crate::tiles::Tiles;
+
+/// Limits on how far the solver searches.
+#[derive(Clone, Debug)]
+pub struct SolverConfig {
+    /// money at the start of the level
+    pub budget: usize,
+    /// what can be bought, with its prices and stock
+    pub shop: Catalogue,
+    /// give up on runs that take longer than this
+    pub max_turns: usize,
+    /// states kept after each turn, every state is kept if not set
+    pub beam_width: Option<usize>,
+    /// stop after simulating this many turns in total
+    pub max_nodes: usize,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        SolverConfig {
+            budget: 10,
+            shop: Catalogue::default(),
+            max_turns: 40,
+            beam_width: Some(200),
+            max_nodes: 200_000,
+        }
+    }
+}
+
+/// A winning run, in the order the player would do it.
+#[derive(Clone, Debug)]
+pub struct Solution {
+    pub actions: Vec<Action>,
+    pub turns: usize,
+    /// carrots harvested over the whole run
+    pub harvested: usize,
+    /// money left at the end of the run
+    pub money: usize,
+}
+
+impl Solution {
+    /// The solution as a replay that can be watched with `--replay`.
+    pub fn replay(&self, map: PathBuf, budget: usize) -> Replay {
+        let mut replay = Replay::new(map, budget);
+        for a in self.actions.iter() {
+            replay.record(*a);
+        }
+        replay
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SolveReport {
+    /// the solution with the fewest turns found, None if the level wasn't beaten
+    pub solution: Option<Solution>,
+    /// turns simulated during the search
+    pub explored: usize,
+    /// true if every reachable state up to `max_turns` was tried,
+    /// so a missing solution means there is none in that many turns
+    pub exhaustive: bool,
+}
+
+struct Node {
+    state: SimState,
+    money: usize,
+    harvested: usize,
+    /// what has been bought so far, for the prices and stock left
+    purchases: Purchases,
+    actions: Vec<Action>,
+}
+
+/// Search for the shortest way to beat the map loaded into `sim`.
+///
+/// Each turn the solver either buys and places one tile or places nothing,
+/// then runs the turn. States are searched a turn at a time so the first
+/// solution found takes the fewest turns, with `beam_width` keeping only the
+/// richest states of each turn. `sim` is left in an unspecified state.
+pub fn solve(sim: &mut Simulation, config: &SolverConfig) -> SolveReport {
+    let mut report = SolveReport { solution: None, explored: 0, exhaustive: true };
+
+    sim.check(config.budget);
+    let start = Node {
+        state: sim.save_state(),
+        money: config.budget,
+        harvested: 0,
+        purchases: Purchases::new(),
+        actions: Vec::new(),
+    };
+    let mut layer = vec![start];
+    let mut visited = HashSet::new();
+    for turn in 0..=config.max_turns {
+        for node in layer.iter() {
+            if let Some(s) = win(sim, node, &config.shop) {
+                report.solution = Some(s);
+                return report;
+            }
+        }
+        if turn == config.max_turns {
+            break;
+        }
+        let mut next = Vec::new();
+        for node in layer.iter() {
+            for m in moves(node, &config.shop) {
+                if report.explored >= config.max_nodes {
+                    report.exhaustive = false;
+                    return report;
+                }
+                if let Some(child) = expand(sim, node, m) {
+                    report.explored += 1;
+                    let key = ron::to_string(&(&child.state, child.money, &child.purchases))
+                        .unwrap_or_default();
+                    if visited.insert(key) {
+                        next.push(child);
+                    }
+                }
+            }
+        }
+        if let Some(width) = config.beam_width {
+            if next.len() > width {
+                next.sort_by_key(|n| std::cmp::Reverse(score(n)));
+                next.truncate(width);
+                report.exhaustive = false;
+            }
+        }
+        if next.is_empty() {
+            return report;
+        }
+        layer = next;
+    }
+    report
+}
+
+/// Placements worth trying from `node`, None is placing nothing.
+fn moves(node: &Node, shop: &Catalogue) -> Vec<Option<(Tiles, usize, usize, usize)>> {
+    let mut moves = vec![None];
+    let purchases = turn_purchases(node);
+    for item in shop.items.iter().filter(|i| i.tile != Tiles::Key) {
+        let cost = match shop.affordable(item.tile, node.money, &purchases) {
+            Some(c) => c,
+            None => continue,
+        };
+        for (i, t) in node.state.map.iter().enumerate() {
+            if *t != Tiles::None && *t != Tiles::Door && *t != item.tile {
+                moves.push(Some((item.tile, i % node.state.w, i / node.state.w, cost)));
+            }
+        }
+    }
+    moves
+}
+
+/// The purchases of `node` counted against the turn it is about to play.
+fn turn_purchases(node: &Node) -> Purchases {
+    let mut purchases = node.purchases.clone();
+    purchases.set_turn(node.state.turn_count);
+    purchases
+}
+
+/// Make the placement then run a turn, None if the placement did nothing or the level was lost.
+fn expand(sim: &mut Simulation, node: &Node, m: Option<(Tiles, usize, usize, usize)>) -> Option<Node> {
+    sim.restore(node.state.clone()).ok()?;
+    let mut money = node.money;
+    let mut purchases = turn_purchases(node);
+    let mut actions = node.actions.clone();
+    if let Some((tile, x, y, cost)) = m {
+        let step = sim.step_count();
+        if !sim.place_tile(tile, x, y).ok()? {
+            return None;
+        }
+        money -= cost;
+        purchases.buy(tile);
+        actions.push(Action::Buy { tile, cost });
+        actions.push(Action::Place { tile, x, y, step });
+    }
+    actions.push(Action::NextTurn);
+    let harvested = sim.advance_turn().harvested;
+    sim.check(money + harvested);
+    if sim.lose {
+        return None;
+    }
+    Some(Node {
+        state: sim.save_state(),
+        money: money + harvested,
+        harvested: node.harvested + harvested,
+        purchases,
+        actions,
+    })
+}
+
+/// A solution ending at `node` if its objectives are already met,
+/// or if buying the key and putting it in the door meets them.
+fn win(sim: &mut Simulation, node: &Node, shop: &Catalogue) -> Option<Solution> {
+    let solution = |actions, money| Solution {
+        actions,
+        turns: node.state.turn_count,
+        harvested: node.harvested,
+        money,
+    };
+    if node.state.complete {
+        return Some(solution(node.actions.clone(), node.money));
+    }
+    let cost = shop.affordable(Tiles::Key, node.money, &turn_purchases(node))?;
+    let door = node.state.map.iter().position(|t| *t == Tiles::Door)?;
+    let (x, y) = (door % node.state.w, door / node.state.w);
+    sim.restore(node.state.clone()).ok()?;
+    sim.place_tile(Tiles::Key, x, y).ok()?;
+    if sim.check(node.money - cost) != Outcome::Won {
+        return None;
+    }
+    let mut actions = node.actions.clone();
+    actions.push(Action::Buy { tile: Tiles::Key, cost });
+    actions.push(Action::Place { tile: Tiles::Key, x, y, step: node.state.step_count });
+    Some(solution(actions, node.money - cost))
+}
+
+/// States with more money, then more carrots still growing, are closer to winning.
+fn score(node: &Node) -> (usize, usize) {
+    let carrots = node.state.map.iter().filter(|t| **t == Tiles::Carrot).count();
+    (node.money, carrots)
+}
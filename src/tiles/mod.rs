@@ -1,10 +1,30 @@
-
 mod tilemap;
+mod resolve;
+mod rules;
+mod state;
+mod snapshot;
+mod tileset;
+mod props;
+mod structure;
+#[cfg(feature = "sdl")]
+mod sprites;
 pub mod types;
-use sdl_helper::Camera;
-pub use tilemap::{Tilemap, Choice, TILE};
+pub use tilemap::{Tilemap, Choice, Move};
+pub use resolve::{Resolver, ResolveMode};
+pub use rules::{Rules, TileRule, RuleRef, Effect, RulesError};
+pub use state::TileState;
+pub use snapshot::{MapSnapshot, MapDiff, CellDiff};
+pub use tileset::{TileTypes, GAME_STATE_TILESET};
+pub use props::TileProps;
+pub use structure::Structure;
+#[cfg(feature = "sdl")]
+pub use sprites::{TileSprites, TILE};
+
+use std::rc::Rc;
 
-#[derive(Hash, Eq, PartialEq, Clone, Copy, Debug)]
+use serde::{Serialize, Deserialize};
+
+#[derive(Hash, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum Tiles {
     None,
     Grass,
@@ -16,8 +36,15 @@ pub enum Tiles {
     Water,
     Ice,
     Key,
+    Fire,
+    Ash,
+    Greenhouse,
+    Fence,
+    Scarecrow,
 }
 
+/// Simulation side of a tile, holds no rendering state so
+/// that the rules can run without a window.
 pub trait Tile {
     fn tile(&self) -> Tiles {
         Tiles::None
@@ -25,19 +52,63 @@ pub trait Tile {
 
     fn pos(&self) -> (usize, usize);
 
+    /// called when the simulation moves the tile to another cell
+    fn set_pos(&mut self, _x: usize, _y: usize) {
+
+    }
+
+    /// true if the tile walks around and can be given a path
+    fn movable(&self) -> bool {
+        false
+    }
+
+    /// give a movable tile cells to visit in order
+    fn set_path(&mut self, _path: Vec<(usize, usize)>) {
+
+    }
+
+    /// cells the tile visits in order
+    fn path(&self) -> &[(usize, usize)] {
+        &[]
+    }
+
+    /// copy of the tile with its internal state, for save files
+    fn save(&self) -> TileState;
+
+    /// give back the rule a restored tile lost when it was saved
+    fn set_rule(&mut self, _rule: Rc<TileRule>) {
+
+    }
+
+    /// set up the starting state a map gives this tile
+    fn init(&mut self, _props: &TileProps) {
+
+    }
+
     fn removed(&mut self) -> bool {
         false
     }
 
-    fn update(&mut self, _map: &mut Tilemap) {
-        
+    /// steps lived so far for tiles that count towards their rule's
+    /// lifetime themselves, the simulation counts it if `None`
+    fn age(&self) -> Option<usize> {
+        None
     }
 
-    fn draw(&self, _cam: &mut Camera) {
+    fn update(&mut self, _map: &mut Tilemap) {
 
     }
 
     fn interact(&mut self, _tile:Tiles) {
-        
+
+    }
+
+    /// index into this tile's row of sprites
+    fn frame(&self) -> usize {
+        0
+    }
+
+    fn frozen(&self) -> bool {
+        false
     }
 }
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+/// Custom properties set in Tiled that give a tile its starting state,
+/// either on a tile of the `game_state` tileset or on an object in a
+/// GameState object layer covering the cell.
+///
+/// - Carrot: `hp`, `growth`, `harvestable`, `frozen`
+/// - Goat: `charges`, `sleep`, `frozen`, `move_period` (steps between moves,
+///   goats stay still unless the map or rules set it),
+///   `hunt_range` (0 walks towards the most roots next to it)
+/// - Root: `age`, `max_age`, `mature`, `frozen`
+/// - Bush: `growth`, `frozen`
+/// - Water: `frozen`
+/// - frozen tiles: `thaw`, steps they stay frozen once no Ice reaches them
+/// - Fire: `burnt`, steps it has already burnt for out of its lifetime
+/// - any tile: `sellable`, lets the player sell it
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TileProps(HashMap<String, i64>);
+
+impl TileProps {
+    pub fn new() -> TileProps {
+        TileProps(HashMap::new())
+    }
+
+    /// The integer and bool properties of a tiled object or layer.
+    pub fn from_tiled(props: &tiled::Properties) -> TileProps {
+        let mut p = TileProps::new();
+        for (k, v) in props.integers.iter() {
+            p.set(k, *v);
+        }
+        for (k, v) in props.booleans.iter() {
+            p.set(k, *v as i64);
+        }
+        p
+    }
+
+    pub fn set(&mut self, name: &str, value: i64) {
+        self.0.insert(name.to_string(), value);
+    }
+
+    /// `name` as a count, negative numbers are 0.
+    pub fn int(&self, name: &str) -> Option<usize> {
+        self.0.get(name).map(|v| (*v).max(0) as usize)
+    }
+
+    pub fn flag(&self, name: &str) -> Option<bool> {
+        self.0.get(name).map(|v| *v != 0)
+    }
+
+    /// Add the properties of `other`, replacing any with the same name.
+    pub fn extend(&mut self, other: &TileProps) {
+        for (k, v) in other.0.iter() {
+            self.0.insert(k.clone(), *v);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
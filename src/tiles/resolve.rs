@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use super::{Tiles, Choice};
+
+/// How to settle several tiles trying to change the same cell in one step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResolveMode {
+    /// A cell claimed by more than one kind of tile keeps what was there first.
+    FirstWins,
+    /// Only the kind of tile with the highest priority gets applied,
+    /// a tie between different kinds keeps what was there first.
+    StrongestWins,
+    /// Every change is applied, weakest first, so the strongest ends up on top.
+    Merge,
+}
+
+/// Priority table and mode used to resolve the `Choice`s made during a step.
+/// The result only depends on the set of choices for a cell, never on the
+/// order they were pushed in.
+#[derive(Clone, Debug)]
+pub struct Resolver {
+    pub mode: ResolveMode,
+    base: HashMap<Tiles, u8>,
+    pairs: HashMap<(Tiles, Tiles), u8>,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        let mut base = HashMap::new();
+        base.insert(Tiles::Carrot, 1);
+        base.insert(Tiles::Water, 1);
+        base.insert(Tiles::Root, 2);
+        base.insert(Tiles::Bush, 3);
+        base.insert(Tiles::Grass, 4);
+        base.insert(Tiles::Ice, 5);
+        Resolver {
+            mode: ResolveMode::Merge,
+            base,
+            pairs: HashMap::new(),
+        }
+    }
+}
+
+impl Resolver {
+    pub fn new(mode: ResolveMode) -> Resolver {
+        Resolver { mode, ..Default::default() }
+    }
+
+    /// Priority of `incoming` on any cell.
+    pub fn set_priority(&mut self, incoming: Tiles, priority: u8) {
+        self.base.insert(incoming, priority);
+    }
+
+    /// Priority of `incoming` on a cell holding `occupant`, overrides the base priority.
+    pub fn set_pair_priority(&mut self, incoming: Tiles, occupant: Tiles, priority: u8) {
+        self.pairs.insert((incoming, occupant), priority);
+    }
+
+    pub fn priority(&self, incoming: Tiles, occupant: Tiles) -> u8 {
+        match self.pairs.get(&(incoming, occupant)) {
+            Some(p) => *p,
+            None => *self.base.get(&incoming).unwrap_or(&0),
+        }
+    }
+
+    /// Takes every choice made for one cell holding `occupant` and returns
+    /// the ones to apply, in the order they should be applied.
+    pub fn resolve(&self, occupant: Tiles, mut choices: Vec<Choice>) -> Vec<Choice> {
+        choices.retain(|c| c.dst != occupant);
+        choices.sort_by_key(|c| (self.priority(c.dst, occupant), c.dst, c.src));
+        let kinds = Self::kinds(&choices);
+        match self.mode {
+            ResolveMode::Merge => choices,
+            ResolveMode::FirstWins => {
+                if kinds.len() > 1 {
+                    Vec::new()
+                } else {
+                    choices
+                }
+            },
+            ResolveMode::StrongestWins => {
+                let strongest = match choices.last() {
+                    Some(c) => self.priority(c.dst, occupant),
+                    None => return choices,
+                };
+                let top: Vec<Tiles> = kinds.into_iter()
+                    .filter(|t| self.priority(*t, occupant) == strongest).collect();
+                if top.len() > 1 {
+                    return Vec::new();
+                }
+                choices.into_iter().filter(|c| c.dst == top[0]).collect()
+            },
+        }
+    }
+
+    fn kinds(choices: &[Choice]) -> Vec<Tiles> {
+        let mut kinds: Vec<Tiles> = choices.iter().map(|c| c.dst).collect();
+        kinds.dedup();
+        kinds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn choice(src: Tiles, dst: Tiles) -> Choice {
+        Choice { i: 0, x: 0, y: 0, src, dst }
+    }
+
+    fn dsts(choices: Vec<Choice>) -> Vec<Tiles> {
+        choices.iter().map(|c| c.dst).collect()
+    }
+
+    #[test]
+    fn first_wins_keeps_occupant_on_conflict() {
+        let r = Resolver::new(ResolveMode::FirstWins);
+        let conflict = vec![choice(Tiles::Bush, Tiles::Bush), choice(Tiles::Root, Tiles::Root)];
+        assert!(r.resolve(Tiles::Grass, conflict).is_empty());
+        let same = vec![choice(Tiles::Root, Tiles::Root), choice(Tiles::Carrot, Tiles::Root)];
+        assert_eq!(dsts(r.resolve(Tiles::Grass, same)), vec![Tiles::Root, Tiles::Root]);
+    }
+
+    #[test]
+    fn strongest_wins_applies_highest_priority() {
+        let r = Resolver::new(ResolveMode::StrongestWins);
+        let conflict = vec![choice(Tiles::Bush, Tiles::Bush), choice(Tiles::Root, Tiles::Root)];
+        assert_eq!(dsts(r.resolve(Tiles::Grass, conflict)), vec![Tiles::Bush]);
+        // Carrot and Water share a priority
+        let tie = vec![choice(Tiles::Carrot, Tiles::Carrot), choice(Tiles::Water, Tiles::Water)];
+        assert!(r.resolve(Tiles::Grass, tie).is_empty());
+    }
+
+    #[test]
+    fn merge_applies_weakest_first() {
+        let r = Resolver::new(ResolveMode::Merge);
+        let conflict = vec![
+            choice(Tiles::Ice, Tiles::Ice),
+            choice(Tiles::Bush, Tiles::Bush),
+            choice(Tiles::Root, Tiles::Root),
+            choice(Tiles::Root, Tiles::Grass),
+        ];
+        assert_eq!(dsts(r.resolve(Tiles::Grass, conflict)), vec![Tiles::Root, Tiles::Bush, Tiles::Ice]);
+    }
+
+    #[test]
+    fn pair_priority_overrides_base() {
+        let mut r = Resolver::new(ResolveMode::StrongestWins);
+        r.set_pair_priority(Tiles::Root, Tiles::Carrot, 9);
+        let conflict = vec![choice(Tiles::Bush, Tiles::Bush), choice(Tiles::Root, Tiles::Root)];
+        assert_eq!(dsts(r.resolve(Tiles::Carrot, conflict.clone())), vec![Tiles::Root]);
+        assert_eq!(dsts(r.resolve(Tiles::Grass, conflict)), vec![Tiles::Bush]);
+    }
+}
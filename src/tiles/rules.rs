@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::ops::Deref;
+use std::rc::Rc;
+use std::fmt;
+
+use serde::{Serialize, Deserialize};
+
+use super::{Tiles, Tilemap};
+
+const DEFAULT_RULES: &str = include_str!("../../resources/rules.ron");
+
+/// What happens to a tile when another tile reaches it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Effect {
+    Remove,
+    Freeze,
+    Damage,
+    Water,
+    /// the tile burns and is replaced by the fire
+    Burn,
+    /// a fire goes out
+    Extinguish,
+    /// Ice melts and frozen tiles thaw
+    Melt,
+    /// a structure or aura keeps the tile out of the cells it covers
+    Block,
+    /// tiles inside an aura take no damage from the tile
+    Shelter,
+}
+
+/// Behaviour of one type of tile.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TileRule {
+    /// steps between each time the tile acts
+    pub period: usize,
+    /// cells, relative to the tile, that it acts on
+    pub spread: Vec<(i64, i64)>,
+    /// only cells holding one of these are acted on, any cell if empty
+    pub targets: Vec<Tiles>,
+    /// what is put in the cells acted on, the tile itself if not set
+    pub produces: Option<Tiles>,
+    pub interactions: HashMap<Tiles, Effect>,
+    /// what is left when another tile removes this one, that tile if not listed
+    pub leaves: HashMap<Tiles, Tiles>,
+    /// steps before the tile turns into `remains`, lives forever if not set
+    pub lifetime: Option<usize>,
+    pub remains: Tiles,
+    /// numbers specific to a tile type, like a carrot's max hp
+    pub params: HashMap<String, usize>,
+}
+
+impl Default for TileRule {
+    fn default() -> Self {
+        TileRule {
+            period: 1,
+            spread: Vec::new(),
+            targets: Vec::new(),
+            produces: None,
+            interactions: HashMap::new(),
+            leaves: HashMap::new(),
+            lifetime: None,
+            remains: Tiles::Grass,
+            params: HashMap::new(),
+        }
+    }
+}
+
+impl TileRule {
+    pub fn effect(&self, other: Tiles) -> Option<Effect> {
+        self.interactions.get(&other).copied()
+    }
+
+    /// Cells a structure covers across and down, from its "width" and
+    /// "height" params. `None` for tiles that take up a single cell.
+    pub fn footprint(&self) -> Option<(usize, usize)> {
+        match (self.param("width"), self.param("height")) {
+            (0, _) | (_, 0) => None,
+            (w, h) => Some((w, h)),
+        }
+    }
+
+    /// What is put in this tile's cell when `other` removes it.
+    pub fn leaves(&self, other: Tiles) -> Tiles {
+        *self.leaves.get(&other).unwrap_or(&other)
+    }
+
+    /// How many cells in each direction the tile's aura reaches,
+    /// from its "radius" param. `None` for tiles without an aura.
+    pub fn aura(&self) -> Option<usize> {
+        match self.param("radius") {
+            0 => None,
+            r => Some(r),
+        }
+    }
+
+    pub fn param(&self, name: &str) -> usize {
+        *self.params.get(name).unwrap_or(&0)
+    }
+
+    /// Counts a step on `timer`, true once every `period` steps.
+    pub fn acts(&self, timer: &mut usize) -> bool {
+        *timer += 1;
+        if *timer >= self.period {
+            *timer = 0;
+            return true;
+        }
+        false
+    }
+
+    /// Counts a step of a tile frozen by Ice. Once `thaw` runs out the tile
+    /// thaws, returns true if the tile was frozen this step and shouldn't act.
+    /// This doesn't look for Ice itself: Ice that reaches the tile freezes it
+    /// again each time it acts, which resets `thaw`, so it only runs out once
+    /// no Ice reaches the tile.
+    pub fn thaws(&self, frozen: &mut bool, thaw: &mut usize) -> bool {
+        if !*frozen {
+            return false;
+        }
+        if *thaw > 0 {
+            *thaw -= 1;
+        } else {
+            *frozen = false;
+        }
+        true
+    }
+
+    /// Ask to change every targeted cell in the spread pattern around `(x, y)`,
+    /// returns how many cells were targeted.
+    pub fn spread(&self, src: Tiles, x: usize, y: usize, map: &mut Tilemap) -> usize {
+        let dst = self.produces.unwrap_or(src);
+        let mut count = 0;
+        for (dx, dy) in self.spread.iter() {
+            let (tx, ty) = (x as i64 + dx, y as i64 + dy);
+            if !self.targets.is_empty() &&
+                !self.targets.contains(&map.get_or_none(tx, ty)) {
+                    continue;
+                }
+            map.set(src, tx, ty, dst);
+            count += 1;
+        }
+        count
+    }
+}
+
+/// A tile's rule, shared by every tile of its type. The rule is configuration
+/// rather than state, so it never makes two tiles unequal, even when the
+/// rules were loaded separately.
+#[derive(Clone, Debug, Default)]
+pub struct RuleRef(Rc<TileRule>);
+
+impl From<Rc<TileRule>> for RuleRef {
+    fn from(rule: Rc<TileRule>) -> Self {
+        RuleRef(rule)
+    }
+}
+
+impl Deref for RuleRef {
+    type Target = TileRule;
+
+    fn deref(&self) -> &TileRule {
+        &self.0
+    }
+}
+
+impl PartialEq for RuleRef {
+    fn eq(&self, _: &RuleRef) -> bool {
+        true
+    }
+}
+
+#[derive(Debug)]
+pub enum RulesError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RulesError::Io(e) => write!(f, "could not read rules file: {}", e),
+            RulesError::Parse(e) => write!(f, "could not parse rules file: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+/// The rules for every tile type, tiles missing from the file
+/// get a rule that does nothing.
+#[derive(Clone, Debug)]
+pub struct Rules {
+    tiles: HashMap<Tiles, Rc<TileRule>>,
+    none: Rc<TileRule>,
+    /// tiles with an aura, in order so that overlapping auras always agree
+    auras: Vec<Tiles>,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules::parse(DEFAULT_RULES).expect("default rules file is invalid")
+    }
+}
+
+impl Rules {
+    pub fn load(path: &Path) -> Result<Rules, RulesError> {
+        let text = std::fs::read_to_string(path).map_err(RulesError::Io)?;
+        Rules::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Rules, RulesError> {
+        let tiles: HashMap<Tiles, TileRule> = ron::from_str(text).map_err(RulesError::Parse)?;
+        let mut auras: Vec<Tiles> = tiles.iter()
+            .filter(|(_, r)| r.aura().is_some())
+            .map(|(t, _)| *t)
+            .collect();
+        auras.sort();
+        Ok(Rules {
+            tiles: tiles.into_iter().map(|(t, r)| (t, Rc::new(r))).collect(),
+            none: Rc::new(TileRule::default()),
+            auras,
+        })
+    }
+
+    /// Every tile type with an aura.
+    pub fn auras(&self) -> &[Tiles] {
+        &self.auras
+    }
+
+    pub fn get(&self, tile: Tiles) -> Rc<TileRule> {
+        match self.tiles.get(&tile) {
+            Some(r) => r.clone(),
+            None => self.none.clone(),
+        }
+    }
+}
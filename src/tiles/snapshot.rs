@@ -0,0 +1,125 @@
+use super::{Tiles, TileState, Structure};
+
+/// The grid, every tile object's internal state and the structures
+/// built over them at one point in time.
+#[derive(Clone, PartialEq)]
+pub struct MapSnapshot {
+    pub w: usize,
+    pub h: usize,
+    pub map: Vec<Tiles>,
+    pub tiles: Vec<TileState>,
+    pub structures: Vec<Structure>,
+}
+
+/// A cell that differs between two snapshots.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellDiff {
+    pub x: usize,
+    pub y: usize,
+    pub from: Tiles,
+    pub to: Tiles,
+}
+
+/// Everything that differs between two snapshots of the same map.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MapDiff {
+    /// cells holding a different tile
+    pub cells: Vec<CellDiff>,
+    /// cells holding the same tile, but with different counters like hp or charge
+    pub counters: Vec<(usize, usize)>,
+    /// structures only in the later snapshot
+    pub built: Vec<Structure>,
+    /// structures only in the earlier snapshot
+    pub demolished: Vec<Structure>,
+}
+
+impl MapSnapshot {
+    /// What changed going from `self` to `later`,
+    /// every cell counts as changed if the map sizes differ.
+    pub fn diff(&self, later: &MapSnapshot) -> MapDiff {
+        let mut diff = MapDiff::default();
+        let same_size = self.w == later.w && self.h == later.h;
+        for (i, to) in later.map.iter().enumerate() {
+            let (x, y) = (i % later.w, i / later.w);
+            let from = if same_size { self.map[i] } else { Tiles::None };
+            if from != *to || !same_size {
+                diff.cells.push(CellDiff { x, y, from, to: *to });
+            } else if self.tiles.get(i) != later.tiles.get(i) {
+                diff.counters.push((x, y));
+            }
+        }
+        diff.built = later.structures.iter()
+            .filter(|s| !self.structures.contains(s)).cloned().collect();
+        diff.demolished = self.structures.iter()
+            .filter(|s| !later.structures.contains(s)).cloned().collect();
+        diff
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Tiles {
+        self.map[y * self.w + x]
+    }
+}
+
+impl MapDiff {
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len() + self.counters.len() + self.built.len() + self.demolished.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::Owner;
+    use crate::tiles::{Tile, Tilemap};
+    use crate::tiles::types::Root;
+
+    /// A 2x2 snapshot of `map` with its own copy of the default rules,
+    /// any Roots are `age` steps old.
+    fn snapshot(map: Vec<Tiles>, age: usize, structures: Vec<Structure>) -> MapSnapshot {
+        let board = Tilemap::new();
+        let tiles = map.iter().enumerate().map(|(i, t)| {
+            let (x, y) = (i % 2, i / 2);
+            if *t == Tiles::Root {
+                let mut root = Root::new(x, y, board.rules.get(Tiles::Root));
+                root.age = age;
+                root.save()
+            } else {
+                board.set_tile_obj(*t, x, y).save()
+            }
+        }).collect();
+        MapSnapshot { w: 2, h: 2, map, tiles, structures }
+    }
+
+    #[test]
+    fn same_map_has_no_diff() {
+        let map = vec![Tiles::Goat, Tiles::Grass, Tiles::Root, Tiles::Grass];
+        assert!(snapshot(map.clone(), 1, Vec::new()).diff(&snapshot(map, 1, Vec::new())).is_empty());
+    }
+
+    #[test]
+    fn diff_reports_cells_counters_and_structures() {
+        let fence = Structure { tile: Tiles::Fence, x: 1, y: 0, w: 1, h: 2, owner: Owner::Player };
+        let a = snapshot(vec![Tiles::Goat, Tiles::Grass, Tiles::Root, Tiles::Grass], 1, Vec::new());
+        let b = snapshot(vec![Tiles::Goat, Tiles::Root, Tiles::Root, Tiles::Grass], 2, vec![fence.clone()]);
+        let diff = a.diff(&b);
+        assert_eq!(diff.cells, vec![CellDiff { x: 1, y: 0, from: Tiles::Grass, to: Tiles::Root }]);
+        assert_eq!(diff.counters, vec![(0, 1)]);
+        assert_eq!(diff.built, vec![fence.clone()]);
+        assert!(diff.demolished.is_empty());
+        assert_eq!(diff.len(), 3);
+        assert_eq!(b.diff(&a).demolished, vec![fence]);
+    }
+
+    #[test]
+    fn resized_map_changes_every_cell() {
+        let a = snapshot(vec![Tiles::Grass; 4], 0, Vec::new());
+        let b = MapSnapshot { w: 1, h: 2, map: vec![Tiles::Grass; 2], tiles: Vec::new(), structures: Vec::new() };
+        let diff = a.diff(&b);
+        assert_eq!(diff.cells.len(), 2);
+        assert!(diff.cells.iter().all(|c| c.from == Tiles::None));
+    }
+}
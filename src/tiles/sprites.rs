@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use super::{Tiles, Tile, Structure};
+
+use sdl_helper::{GameObject, resource::Texture, geometry::*, Colour, Camera};
+
+pub const TILE: Vec2 = Vec2::new(19.0, 19.0);
+
+/// Sprites for each tile type, used to draw the simulation's tile objects.
+pub struct TileSprites {
+    pub resources: HashMap<Tiles, Vec<GameObject>>,
+}
+
+impl TileSprites {
+    pub fn new(tiles: Texture) -> TileSprites {
+        TileSprites {
+            resources: Self::load_resources(tiles),
+        }
+    }
+
+    fn load_resources(tiles: Texture) -> HashMap<Tiles, Vec<GameObject>> {
+        let mut r = HashMap::new();
+        r.insert(Tiles::None, vec![
+                     Self::get_tile(tiles, 0, 3)]);
+        r.insert(Tiles::Grass, Vec::new());
+        r.insert(Tiles::Root,
+                 vec![
+                     Self::get_tile(tiles, 0, 1),
+                     Self::get_tile(tiles, 1, 1),
+                     Self::get_tile(tiles, 2, 1),
+                     Self::get_tile(tiles, 3, 1),
+                 ]
+        );
+        r.insert(Tiles::Carrot,
+                 vec![
+                     Self::get_tile(tiles, 0, 0),
+                     Self::get_tile(tiles, 1, 0),
+                     Self::get_tile(tiles, 2, 0),
+                     Self::get_tile(tiles, 3, 0),
+                 ]
+        );
+        r.insert(Tiles::Goat,
+                 vec![
+                     Self::get_tile(tiles, 0, 4),
+                     Self::get_tile(tiles, 1, 4),
+                     Self::get_tile(tiles, 2, 4),
+                     Self::get_tile(tiles, 3, 4),
+                     Self::get_tile(tiles, 4, 4),
+                 ]
+        );
+        r.insert(Tiles::Bush,
+                 vec![
+                     Self::get_tile(tiles, 0, 6),
+                 ]
+        );
+        r.insert(Tiles::Water,
+                 vec![
+                     Self::get_tile(tiles, 0, 5),
+                 ]
+        );
+        r.insert(Tiles::Door,
+                 vec![
+                     Self::get_tile(tiles, 2, 2),
+                 ]
+        );
+        r.insert(Tiles::Ice,
+                 vec![
+                     Self::get_tile(tiles, 0, 7),
+                 ]
+        );
+        r.insert(Tiles::Fire,
+                 vec![
+                     Self::get_tile(tiles, 0, 9),
+                     Self::get_tile(tiles, 1, 9),
+                     Self::get_tile(tiles, 2, 9),
+                 ]
+        );
+        r.insert(Tiles::Ash,
+                 vec![
+                     Self::get_tile(tiles, 0, 10),
+                 ]
+        );
+        r.insert(Tiles::Scarecrow,
+                 vec![
+                     Self::get_tile(tiles, 0, 14),
+                 ]
+        );
+        r.insert(Tiles::Greenhouse,
+                 vec![
+                     Self::get_sprite(tiles, 0, 11, 2, 2),
+                 ]
+        );
+        r.insert(Tiles::Fence,
+                 vec![
+                     Self::get_sprite(tiles, 0, 13, 2, 1),
+                 ]
+        );
+        r
+    }
+
+    pub fn get_tile(tex: Texture, x: usize, y: usize) -> GameObject {
+        Self::get_sprite(tex, x, y, 1, 1)
+    }
+
+    /// A sprite `w` by `h` tiles across with its top left tile at `(x, y)`.
+    pub fn get_sprite(tex: Texture, x: usize, y: usize, w: usize, h: usize) -> GameObject {
+        let (w, h) = (w as f64 * TILE.x, h as f64 * TILE.y);
+        GameObject::new(
+            tex,
+            Rect::new(0.0, 0.0, w, h),
+            Rect::new(
+                x as f64 * TILE.x, y as f64 * TILE.y, w, h
+            ),
+            Vec2::new(1.0, 1.0), Colour::white()
+        )
+    }
+
+    /// Draw a structure as one sprite over all of the cells it covers.
+    pub fn draw_structure(&self, cam: &mut Camera, structure: &Structure) {
+        let mut current = match self.resources.get(&structure.tile).and_then(|f| f.first()) {
+            Some(f) => *f,
+            None => return,
+        };
+        current.rect.x = structure.x as f64 * TILE.x;
+        current.rect.y = structure.y as f64 * TILE.y;
+        current.rect.w = structure.w as f64 * TILE.x;
+        current.rect.h = structure.h as f64 * TILE.y;
+        cam.draw(&current);
+    }
+
+    pub fn draw(&self, cam: &mut Camera, tile: &dyn Tile) {
+        if tile.tile() == Tiles::None {
+            return;
+        }
+        let frames = match self.resources.get(&tile.tile()) {
+            Some(f) => f,
+            None => return,
+        };
+        let mut current = match frames.get(tile.frame()) {
+            Some(f) => *f,
+            None => return,
+        };
+        current.rect.x = tile.pos().0 as f64 * TILE.x;
+        current.rect.y = tile.pos().1 as f64 * TILE.y;
+        if tile.frozen() {
+            current.colour.r = 100;
+            current.colour.g = 100;
+            current.colour.b = 255;
+        }
+        cam.draw(&current);
+    }
+}
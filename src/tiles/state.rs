@@ -0,0 +1,41 @@
+use serde::{Serialize, Deserialize};
+
+use super::{Tile, Rules, types};
+
+/// A tile object with all of its internal counters, used to save and restore a game.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub enum TileState {
+    Empty(types::Empty),
+    Grass(types::Grass),
+    Root(types::Root),
+    Carrot(types::Carrot),
+    Goat(types::Goat),
+    Bush(types::Bush),
+    Water(types::Water),
+    Door(types::Door),
+    Ice(types::Ice),
+    Fire(types::Fire),
+    Ash(types::Ash),
+    Scarecrow(types::Scarecrow),
+}
+
+impl TileState {
+    pub fn into_tile(self, rules: &Rules) -> Box<dyn Tile> {
+        let mut t: Box<dyn Tile> = match self {
+            TileState::Empty(t) => Box::new(t),
+            TileState::Grass(t) => Box::new(t),
+            TileState::Root(t) => Box::new(t),
+            TileState::Carrot(t) => Box::new(t),
+            TileState::Goat(t) => Box::new(t),
+            TileState::Bush(t) => Box::new(t),
+            TileState::Water(t) => Box::new(t),
+            TileState::Door(t) => Box::new(t),
+            TileState::Ice(t) => Box::new(t),
+            TileState::Fire(t) => Box::new(t),
+            TileState::Ash(t) => Box::new(t),
+            TileState::Scarecrow(t) => Box::new(t),
+        };
+        t.set_rule(rules.get(t.tile()));
+        t
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Serialize, Deserialize};
+
+use crate::sim::Owner;
+
+use super::{Tiles, TileRule};
+
+/// A tile covering a rectangle of cells, like a Greenhouse. The cells under it
+/// keep their own tiles, and whatever reaches them goes through the structure first.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Structure {
+    pub tile: Tiles,
+    /// top left cell, the one it is built and sold from
+    pub x: usize,
+    pub y: usize,
+    pub w: usize,
+    pub h: usize,
+    /// who built it, which decides if the player can sell it
+    pub owner: Owner,
+}
+
+impl Structure {
+    /// A `tile` with its top left cell at `(x, y)`,
+    /// `None` if the rule doesn't give it a footprint.
+    pub fn new(tile: Tiles, x: usize, y: usize, owner: Owner, rule: &TileRule) -> Option<Structure> {
+        let (w, h) = rule.footprint()?;
+        Some(Structure { tile, x, y, w, h, owner })
+    }
+
+    /// True if `(x, y)` is the cell it was built from.
+    pub fn anchored_at(&self, x: usize, y: usize) -> bool {
+        self.x == x && self.y == y
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.w && y < self.y + self.h
+    }
+
+    /// Every cell it covers, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> {
+        let (x, y, w, h) = (self.x, self.y, self.w, self.h);
+        (y..y + h).flat_map(move |cy| (x..x + w).map(move |cx| (cx, cy)))
+    }
+}
@@ -1,188 +1,273 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 
-use super::{Tiles, Tile, types};
+use crate::error::GameError;
+use crate::sim::Owner;
 
-use sdl_helper::{map::tiled::{self, Text}, GameObject, resource::Texture, geometry::*, Colour};
+use super::{Tiles, Tile, Rules, Effect, MapSnapshot, Structure, TileTypes, TileProps, types};
 
-pub const TILE: Vec2 = Vec2::new(19.0, 19.0);
-
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub struct Choice {
     pub i: usize,
     pub x: usize,
     pub y: usize,
+    /// the tile asking for the change, `Tiles::None` if it is the
+    /// tile in the cell leaving it
     pub src: Tiles,
     pub dst: Tiles,
 }
 
+/// A tile asking to move from its cell to another, made during a step
+/// and applied once every tile has updated.
+#[derive(Clone, Copy, Debug)]
+pub struct Move {
+    pub from: usize,
+    pub to: usize,
+    pub src: Tiles,
+    /// what the tile saw in the cell it is moving to
+    pub onto: Tiles,
+}
+
+#[derive(Default)]
 pub struct Tilemap {
     pub map: Vec<Tiles>,
     pub map_updates: Vec<Choice>,
+    pub moves: Vec<Move>,
     pub w: usize,
     pub h: usize,
-    pub resources: HashMap<Tiles, Vec<GameObject>>,
     pub harvestable: Vec<(usize, usize)>,
+    pub rules: Rules,
+    /// starting state the map gives the tile in each cell
+    pub init: HashMap<usize, TileProps>,
+    structures: Vec<Structure>,
+    /// index into `structures` of the one covering each cell
+    covered: Vec<Option<usize>>,
 }
 
 impl Tilemap {
-    pub fn new(tiles: Texture) -> Tilemap {
+    pub fn new() -> Tilemap {
         Tilemap {
             map: Vec::new(), w: 0, h: 0,
             map_updates: Vec::new(),
-            resources: Self::load_resources(tiles),
+            moves: Vec::new(),
             harvestable: Vec::new(),
+            rules: Rules::default(),
+            init: HashMap::new(),
+            structures: Vec::new(),
+            covered: Vec::new(),
         }
     }
 
-    fn load_resources(tiles: Texture) -> HashMap<Tiles, Vec<GameObject>> {
-        let mut r = HashMap::new();
-        r.insert(Tiles::None, vec![
-                     Self::get_tile(tiles, 0, 3)]);
-        r.insert(Tiles::Grass, Vec::new());
-        r.insert(Tiles::Root,
-                 vec![
-                     Self::get_tile(tiles, 0, 1),
-                     Self::get_tile(tiles, 1, 1),
-                     Self::get_tile(tiles, 2, 1),
-                     Self::get_tile(tiles, 3, 1),
-                 ]
-        );
-        r.insert(Tiles::Carrot,
-                 vec![
-                     Self::get_tile(tiles, 0, 0),
-                     Self::get_tile(tiles, 1, 0),
-                     Self::get_tile(tiles, 2, 0),
-                     Self::get_tile(tiles, 3, 0),
-                 ]
-        );
-        r.insert(Tiles::Goat,
-                 vec![
-                     Self::get_tile(tiles, 0, 4),
-                     Self::get_tile(tiles, 1, 4),
-                     Self::get_tile(tiles, 2, 4),
-                     Self::get_tile(tiles, 3, 4),
-                     Self::get_tile(tiles, 4, 4),
-                 ]
-        );
-        r.insert(Tiles::Bush,
-                 vec![
-                     Self::get_tile(tiles, 0, 6),
-                 ]
-        );
-        r.insert(Tiles::Water,
-                 vec![
-                     Self::get_tile(tiles, 0, 5),
-                 ]
-        );
-        r.insert(Tiles::Door,
-                 vec![
-                     Self::get_tile(tiles, 2, 2),
-                 ]
-        );
-        r.insert(Tiles::Ice,
-                 vec![
-                     Self::get_tile(tiles, 0, 7),
-                 ]
-        );
-        r
-    }
-
-    pub fn get_tile(tex: Texture, x: usize, y: usize) -> GameObject {
-        GameObject::new(
-            tex,
-            Rect::new(0.0, 0.0, TILE.x, TILE.y),
-            Rect::new(
-                x as f64 * TILE.x, y as f64 * TILE.y, TILE.x, TILE.y
-            ),
-            Vec2::new(1.0, 1.0), Colour::white()
-        )
-    }
-
-    pub fn set_map(&mut self, map: &tiled::Map) {
-        self.w = map.width as usize;
-        self.h = map.height as usize;
-
-        self.map.clear();
-        self.map.resize(self.w * self.h, Tiles::None);
-        for layer in map.layers.iter() {
-            if match layer.props.booleans.get("GameState") {
-                Some(v) => *v,
-                None => false,
-            } {
-                for x in 0..layer.width {
-                    for y in 0..layer.height {
-                        let i = self.bi(x as usize, y as usize);
-                        //println!("Tile ID: {}", layer.tiles[i]);
-                        self.map[i] = match layer.tiles[i] {
-                            0 => Tiles::Grass,
-                            1 => Tiles::Root,
-                            2 => Tiles::Carrot,
-                            3 => Tiles::None,
-                            4 => Tiles::Bush,
-                            5 => Tiles::Water,
-                            6 => Tiles::Door,
-                            7 => Tiles::Ice,
-                            _ => panic!("unrecognized tile"),
-                        }
-                    }
+    /// Read the board from the map's GameState layer, using `types` to tell what each tile is.
+    /// The board is left as it was if the layer is missing or malformed.
+    pub fn set_map(&mut self, map: &tiled::Map, types: &TileTypes) -> Result<(), GameError> {
+        let w = map.width as usize;
+        let h = map.height as usize;
+        let layer = match map.layers.iter().find(|l| match l.props.booleans.get("GameState") {
+            Some(v) => *v,
+            None => false,
+        }) {
+            Some(l) => l,
+            None => return Err(GameError::NoGameState),
+        };
+        if layer.width as usize != w || layer.height as usize != h || layer.tiles.len() != w * h {
+            return Err(GameError::LayerSize {
+                width: layer.width as usize,
+                height: layer.height as usize,
+                tiles: layer.tiles.len(),
+            });
+        }
+
+        let mut tiles = Vec::with_capacity(w * h);
+        let mut init: HashMap<usize, TileProps> = HashMap::new();
+        for (i, id) in layer.tiles.iter().enumerate() {
+            tiles.push(match types.get(*id) {
+                Some(t) => t,
+                None => return Err(GameError::UnknownTile { x: i % w, y: i / w, id: *id }),
+            });
+            if let Some(p) = types.props(*id) {
+                init.insert(i, p.clone());
+            }
+        }
+        // objects on a GameState object layer override the tileset's properties
+        let tw = (map.tile_width.max(1)) as f64;
+        let th = (map.tile_height.max(1)) as f64;
+        for group in map.obj_groups.iter()
+            .filter(|g| g.props.booleans.get("GameState").copied().unwrap_or(false)) {
+            for obj in group.objs.iter() {
+                let props = TileProps::from_tiled(&obj.props);
+                let cx = obj.rect.x + obj.rect.w / 2.0;
+                let cy = obj.rect.y + obj.rect.h / 2.0;
+                if cx < 0.0 || cy < 0.0 || cx >= w as f64 * tw || cy >= h as f64 * th {
+                    return Err(GameError::OutOfRange { x: (cx / tw) as usize, y: (cy / th) as usize });
                 }
+                let i = (cy / th) as usize * w + (cx / tw) as usize;
+                init.entry(i).or_default().extend(&props);
             }
         }
+        self.w = w;
+        self.h = h;
+        self.map = tiles;
+        self.init = init;
+        self.set_structures(Vec::new());
+        Ok(())
     }
 
     pub fn set_tile_objs(&self, tile_objs: &mut Vec<Box<dyn Tile>>) {
         tile_objs.clear();
         for y in 0..self.h {
             for x in 0..self.w {
-                tile_objs.push(
-                    self.set_tile_obj(self.get(x, y), x, y)
-                )
+                let mut obj = self.set_tile_obj(self.get(x, y), x, y);
+                if let Some(p) = self.init.get(&self.index(x, y)) {
+                    obj.init(p);
+                }
+                tile_objs.push(obj)
             }
         }
     }
 
     pub fn set_tile_obj(&self, tile: Tiles, x: usize, y: usize) -> Box<dyn Tile> {
+        let rule = self.rules.get(tile);
         match tile {
-            Tiles::Root => Box::new(
-                types::Root::new(x, y, self.resources.get(&Tiles::Root).unwrap().clone())
-            ),
-            Tiles::Grass => Box::new(
-                types::Grass::new(x, y),
-            ),
-            Tiles::Carrot => Box::new(
-                types::Carrot::new(x, y, self.resources.get(&Tiles::Carrot).unwrap().clone()),
-            ),
-            Tiles::Goat => {
-                Box::new(
-                types::Goat::new(x, y, self.resources.get(&Tiles::Goat).unwrap().clone()),
-                )},
-            Tiles::Bush => {
-                Box::new(
-                types::Bush::new(x, y, self.resources.get(&Tiles::Bush).unwrap().clone()),
-		)},
-            Tiles::Water => {
-                Box::new(
-                    types::Water::new(x, y, self.resources.get(&Tiles::Water).unwrap().clone()),
-		)},
-            Tiles::Door => {
-                Box::new(
-                    types::Door::new(x, y, self.resources.get(&Tiles::Door).unwrap().clone()),
-		)},
-            Tiles::Ice => {
-                Box::new(
-                    types::Ice::new(x, y, self.resources.get(&Tiles::Ice).unwrap().clone()),
-		)},
-
+            Tiles::Root => Box::new(types::Root::new(x, y, rule)),
+            Tiles::Grass => Box::new(types::Grass::new(x, y)),
+            Tiles::Carrot => Box::new(types::Carrot::new(x, y, rule)),
+            Tiles::Goat => Box::new(types::Goat::new(x, y, rule)),
+            Tiles::Bush => Box::new(types::Bush::new(x, y, rule)),
+            Tiles::Water => Box::new(types::Water::new(x, y, rule)),
+            Tiles::Door => Box::new(types::Door::new(x, y)),
+            Tiles::Ice => Box::new(types::Ice::new(x, y, rule)),
+            Tiles::Fire => Box::new(types::Fire::new(x, y, rule)),
+            Tiles::Ash => Box::new(types::Ash::new(x, y)),
+            Tiles::Scarecrow => Box::new(types::Scarecrow::new(x, y)),
+            // structures sit over the cells they cover rather than in them
+            Tiles::Greenhouse | Tiles::Fence => Box::new(types::Empty::new(x, y)),
             _ => Box::new(types::Empty::new(x, y)),
         }
     }
 
+    /// Copy of the grid, the state of each tile object in `tile_objs` and the structures.
+    pub fn snapshot(&self, tile_objs: &[Box<dyn Tile>]) -> MapSnapshot {
+        MapSnapshot {
+            w: self.w,
+            h: self.h,
+            map: self.map.clone(),
+            tiles: tile_objs.iter().map(|t| t.save()).collect(),
+            structures: self.structures.clone(),
+        }
+    }
+
+    pub fn structures(&self) -> &[Structure] {
+        &self.structures
+    }
+
+    /// Replace every structure on the board, for restoring a saved game.
+    pub fn set_structures(&mut self, structures: Vec<Structure>) {
+        self.structures = structures;
+        self.covered = vec![None; self.w * self.h];
+        for (s, structure) in self.structures.iter().enumerate() {
+            for (x, y) in structure.cells() {
+                if self.in_range(x, y) {
+                    self.covered[y * self.w + x] = Some(s);
+                }
+            }
+        }
+    }
+
+    /// The structure covering `(x, y)`, if there is one.
+    pub fn structure_at(&self, x: usize, y: usize) -> Option<&Structure> {
+        let i = self.bi(x, y).ok()?;
+        self.covered.get(i).copied().flatten().map(|s| &self.structures[s])
+    }
+
+    /// True if a `tile` structure fits with its top left cell at `(x, y)`. Every
+    /// cell it would cover must be on the board, not covered by another structure
+    /// and hold one of the tiles its rule targets, or anything but None or a Door
+    /// if it has no targets.
+    pub fn can_build(&self, tile: Tiles, x: usize, y: usize) -> bool {
+        let rule = self.rules.get(tile);
+        let structure = match Structure::new(tile, x, y, Owner::Map, &rule) {
+            Some(s) => s,
+            None => return false,
+        };
+        structure.cells().all(|(cx, cy)| {
+            let t = self.get(cx, cy);
+            self.in_range(cx, cy) && self.structure_at(cx, cy).is_none() &&
+                t != Tiles::None && t != Tiles::Door &&
+                (rule.targets.is_empty() || rule.targets.contains(&t))
+        })
+    }
+
+    /// Put a `tile` structure built by `owner` with its top left cell at `(x, y)`,
+    /// false if it doesn't fit.
+    pub fn build(&mut self, tile: Tiles, x: usize, y: usize, owner: Owner) -> bool {
+        if !self.can_build(tile, x, y) {
+            return false;
+        }
+        let structure = match Structure::new(tile, x, y, owner, &self.rules.get(tile)) {
+            Some(s) => s,
+            None => return false,
+        };
+        let mut structures = self.structures.clone();
+        structures.push(structure);
+        self.set_structures(structures);
+        true
+    }
+
+    /// Take away the structure covering `(x, y)`, returning what it was.
+    pub fn demolish(&mut self, x: usize, y: usize) -> Option<Tiles> {
+        let i = self.bi(x, y).ok()?;
+        let s = self.covered.get(i).copied().flatten()?;
+        let mut structures = self.structures.clone();
+        let removed = structures.remove(s);
+        self.set_structures(structures);
+        Some(removed.tile)
+    }
+
+    /// True if a structure or an aura keeps `src` out of `(x, y)`.
+    pub fn blocks(&self, src: Tiles, x: usize, y: usize) -> bool {
+        if self.aura(src, x, y) == Some(Effect::Block) {
+            return true;
+        }
+        match self.structure_at(x, y) {
+            Some(s) => self.rules.get(s.tile).effect(src) == Some(Effect::Block),
+            None => false,
+        }
+    }
+
+    /// What an aura reaching `(x, y)` does to `src`, `None` if no aura there has
+    /// an effect on it. A tile's aura reaches every cell up to its rule's
+    /// "radius" away in each direction, where auras overlap the first tile
+    /// type in `Rules::auras` with an effect on `src` wins.
+    pub fn aura(&self, src: Tiles, x: usize, y: usize) -> Option<Effect> {
+        if !self.in_range(x, y) {
+            return None;
+        }
+        for tile in self.rules.auras() {
+            let rule = self.rules.get(*tile);
+            let (effect, r) = match (rule.effect(src), rule.aura()) {
+                (Some(e), Some(r)) => (e, r),
+                _ => continue,
+            };
+            for ay in y.saturating_sub(r)..=(y + r).min(self.h - 1) {
+                for ax in x.saturating_sub(r)..=(x + r).min(self.w - 1) {
+                    if self.get(ax, ay) == *tile {
+                        return Some(effect);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Ask to change the tile at `(x, y)` to `t`, made by a `src` tile. A structure
+    /// covering the cell gets to stop the change first.
     pub fn set(&mut self, src: Tiles, x: i64, y: i64, t: Tiles) {
         if !self.in_range_i(x, y) {
             return;
         }
-        let i = self.bi(x as usize, y as usize);
+        let i = self.index(x as usize, y as usize);
         if self.map[i] == Tiles::None { return; }
+        if self.blocks(src, x as usize, y as usize) { return; }
         self.map_updates.push(
             Choice {
                 i,
@@ -191,17 +276,67 @@ impl Tilemap {
         );
     }
 
+    /// Ask to move the `src` tile at `from` to the cell `to`, which it
+    /// replaces. The move is dropped if either cell changes first.
+    pub fn move_tile(&mut self, src: Tiles, from: (usize, usize), to: (usize, usize)) {
+        if !self.in_range(from.0, from.1) || !self.in_range(to.0, to.1) {
+            return;
+        }
+        let to = self.index(to.0, to.1);
+        self.moves.push(Move { from: self.index(from.0, from.1), to, src, onto: self.map[to] });
+    }
+
+    /// The first step of a shortest path for a `src` tile from `from` to a cell where
+    /// `goal` is true, moving up, down, left or right through cells where `passable`
+    /// is true, that no structure blocks `src` from, and at most `range` steps away.
+    /// `None` if there is no such cell or `from` is one.
+    pub fn step_towards(&self, src: Tiles, from: (usize, usize), range: usize,
+                        passable: impl Fn(Tiles) -> bool,
+                        goal: impl Fn(usize, usize) -> bool) -> Option<(usize, usize)> {
+        if !self.in_range(from.0, from.1) || goal(from.0, from.1) {
+            return None;
+        }
+        let mut seen = HashSet::from([self.index(from.0, from.1)]);
+        // each cell with how far it is and the first step taken to reach it
+        let mut queue = VecDeque::from([(from, 0, from)]);
+        while let Some(((x, y), dist, step)) = queue.pop_front() {
+            if dist >= range {
+                continue;
+            }
+            for (dx, dy) in [(0, -1), (1, 0), (0, 1), (-1, 0)] {
+                let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+                if !self.in_range_i(nx, ny) {
+                    continue;
+                }
+                let (nx, ny) = (nx as usize, ny as usize);
+                let i = self.index(nx, ny);
+                if seen.contains(&i) || !passable(self.map[i]) || self.blocks(src, nx, ny) {
+                    continue;
+                }
+                let step = if dist == 0 { (nx, ny) } else { step };
+                if goal(nx, ny) {
+                    return Some(step);
+                }
+                seen.insert(i);
+                queue.push_back(((nx, ny), dist + 1, step));
+            }
+        }
+        None
+    }
+
+    /// The tile at `(x, y)`, `Tiles::None` if it is off the board.
     pub fn get(&self, x: usize, y: usize) -> Tiles {
-        let i = self.bi(x, y);
-        self.map[i]
+        match self.bi(x, y) {
+            Ok(i) => self.map[i],
+            Err(_) => Tiles::None,
+        }
     }
 
     pub fn get_or_none(&self, x: i64, y: i64) -> Tiles {
         if !self.in_range_i(x, y) {
             return Tiles::None;
         }
-        let i = self.bi(x as usize, y as usize);
-        self.map[i]
+        self.map[self.index(x as usize, y as usize)]
     }
 
     pub fn _index(&self, i: usize) -> (usize, usize) {
@@ -209,11 +344,15 @@ impl Tilemap {
         (i - (y * self.w), y)
     }
 
-    pub fn bi(&self, x: usize, y:usize) -> usize {
+    pub fn bi(&self, x: usize, y:usize) -> Result<usize, GameError> {
         if !self.in_range(x, y) {
-            eprintln!("out of range on board");
-            return 0;
+            return Err(GameError::OutOfRange { x, y });
         }
+        Ok(self.index(x, y))
+    }
+
+    /// index of a cell already known to be on the board
+    fn index(&self, x: usize, y: usize) -> usize {
         y * self.w + x
     }
 
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use crate::error::GameError;
+
+use super::{Tiles, TileProps};
+
+/// Name of the tileset the GameState layer is drawn with.
+pub const GAME_STATE_TILESET: &str = "game_state";
+
+/// Tiled stores flip flags in the top bits of a gid.
+const GID_MASK: u32 = 0x1FFF_FFFF;
+
+/// Which `Tiles` each tile of the `game_state` tileset stands for,
+/// read from a `type` property on the tiles in Tiled, and the
+/// starting state given by the tiles' other properties.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TileTypes {
+    first_gid: u32,
+    types: HashMap<u32, Tiles>,
+    props: HashMap<u32, TileProps>,
+}
+
+/// The layout of `game_state.tsx` from before tiles had a `type`.
+impl Default for TileTypes {
+    fn default() -> Self {
+        TileTypes {
+            first_gid: 1,
+            types: HashMap::from([
+                (0, Tiles::Root),
+                (1, Tiles::Carrot),
+                (2, Tiles::None),
+                (3, Tiles::Bush),
+                (4, Tiles::Water),
+                (5, Tiles::Door),
+                (6, Tiles::Ice),
+            ]),
+            props: HashMap::new(),
+        }
+    }
+}
+
+impl TileTypes {
+    /// Read the tile types from the `game_state` tileset of the `.tmx` file at `map`.
+    pub fn load(map: &Path) -> Result<TileTypes, GameError> {
+        let text = std::fs::read_to_string(map)
+            .map_err(|e| GameError::Map(format!("{}: {}", map.display(), e)))?;
+        TileTypes::parse(&text, map.parent().unwrap_or(Path::new("")))
+    }
+
+    /// Read the tile types from the text of a `.tmx` file,
+    /// external tilesets are loaded relative to `dir`.
+    /// A `game_state` tileset with no types uses the old fixed layout.
+    pub fn parse(tmx: &str, dir: &Path) -> Result<TileTypes, GameError> {
+        let tmx = tags(tmx).map_err(|e| GameError::Map(e.to_string()))?;
+        let mut i = 0;
+        while i < tmx.len() {
+            let t = &tmx[i];
+            i += 1;
+            if t.name != "tileset" || t.closing {
+                continue;
+            }
+            let first_gid = t.attr("firstgid").and_then(|g| g.parse().ok()).unwrap_or(1);
+            let (types, props) = match t.attr("source") {
+                Some(src) => {
+                    let path = dir.join(src);
+                    let tsx = std::fs::read_to_string(&path)
+                        .map_err(|e| GameError::Map(format!("{}: {}", path.display(), e)))?;
+                    let tsx = tags(&tsx)
+                        .map_err(|e| GameError::Map(format!("{}: {}", path.display(), e)))?;
+                    if tsx.iter().find(|t| t.name == "tileset").and_then(|t| t.attr("name"))
+                        != Some(GAME_STATE_TILESET) {
+                        continue;
+                    }
+                    read_types(&tsx)?
+                },
+                None => {
+                    let end = tmx[i..].iter().position(|t| t.name == "tileset" && t.closing)
+                        .map(|e| i + e).unwrap_or(tmx.len());
+                    if t.attr("name") != Some(GAME_STATE_TILESET) {
+                        i = end;
+                        continue;
+                    }
+                    read_types(&tmx[i..end])?
+                },
+            };
+            if types.is_empty() {
+                return Ok(TileTypes { first_gid, props, ..Default::default() });
+            }
+            return Ok(TileTypes { first_gid, types, props });
+        }
+        Err(GameError::MissingTileset(String::from(GAME_STATE_TILESET)))
+    }
+
+    /// The tile at a GameState layer gid, an empty cell is Grass.
+    pub fn get(&self, gid: u32) -> Option<Tiles> {
+        let gid = gid & GID_MASK;
+        if gid == 0 {
+            return Some(Tiles::Grass);
+        }
+        self.types.get(&gid.checked_sub(self.first_gid)?).copied()
+    }
+
+    /// Starting state set on the tileset tile at a GameState layer gid.
+    pub fn props(&self, gid: u32) -> Option<&TileProps> {
+        self.props.get(&(gid & GID_MASK).checked_sub(self.first_gid)?)
+    }
+}
+
+/// The tile and starting properties of each tile id in a tileset.
+type TilesetTypes = (HashMap<u32, Tiles>, HashMap<u32, TileProps>);
+
+/// Map each tile id in a tileset's tags to the tile named by its `type`
+/// and to its other int and bool properties.
+fn read_types(tags: &[Tag]) -> Result<TilesetTypes, GameError> {
+    let mut types = HashMap::new();
+    let mut props: HashMap<u32, TileProps> = HashMap::new();
+    let mut tile = None;
+    for t in tags.iter() {
+        match (t.name.as_str(), t.closing) {
+            ("tile", false) => {
+                tile = t.attr("id").and_then(|id| id.parse::<u32>().ok());
+                // older versions of Tiled keep the type as an attribute
+                if let (Some(id), Some(name)) = (tile, t.attr("type").or(t.attr("class"))) {
+                    types.insert(id, tile_named(id, name)?);
+                }
+            },
+            ("tile", true) => tile = None,
+            ("property", false) => {
+                let (id, name) = match (tile, t.attr("name")) {
+                    (Some(id), Some(name)) => (id, name),
+                    _ => continue,
+                };
+                let value = t.attr("value").unwrap_or("");
+                if name == "type" {
+                    types.insert(id, tile_named(id, value)?);
+                    continue;
+                }
+                let value = match t.attr("type") {
+                    Some("int") => value.parse().ok(),
+                    Some("bool") => Some((value == "true") as i64),
+                    _ => None,
+                };
+                if let Some(v) = value {
+                    props.entry(id).or_default().set(name, v);
+                }
+            },
+            _ => (),
+        }
+    }
+    Ok((types, props))
+}
+
+fn tile_named(id: u32, name: &str) -> Result<Tiles, GameError> {
+    ron::from_str(name).map_err(|_| GameError::UnknownTileType { id, name: name.to_string() })
+}
+
+/// An xml tag, only as much as is needed to read tileset properties.
+struct Tag {
+    name: String,
+    attrs: Vec<(String, String)>,
+    closing: bool,
+}
+
+impl Tag {
+    fn attr(&self, key: &str) -> Option<&str> {
+        self.attrs.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+}
+
+/// Every tag in `text` in order, a self closing tag is
+/// returned as an opening tag followed by a closing one.
+fn tags(text: &str) -> Result<Vec<Tag>, quick_xml::Error> {
+    let mut reader = Reader::from_str(text);
+    reader.expand_empty_elements(true);
+    let mut tags = Vec::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                let mut attrs = Vec::new();
+                for a in e.attributes() {
+                    let a = a?;
+                    attrs.push((String::from_utf8_lossy(a.key.as_ref()).into_owned(),
+                                a.unescape_value()?.into_owned()));
+                }
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                tags.push(Tag { name, attrs, closing: false });
+            },
+            Event::End(e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                tags.push(Tag { name, attrs: Vec::new(), closing: true });
+            },
+            Event::Eof => return Ok(tags),
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use serde::{Serialize, Deserialize};
+
+use crate::tiles::{Tiles, TileState};
+use crate::tiles::Tile;
+
+/// What is left after a fire, nothing grows on it until it turns back to Grass.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ash {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Ash {
+    pub fn new(x: usize, y: usize) -> Self {
+        Ash {x, y}
+    }
+}
+
+impl Tile for Ash {
+    fn tile(&self) -> Tiles {
+        Tiles::Ash
+    }
+    fn pos(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    fn save(&self) -> TileState {
+        TileState::Ash(self.clone())
+    }
+}
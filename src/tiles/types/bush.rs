@@ -1,50 +1,39 @@
-use sdl_helper::{GameObject, Camera};
+use std::rc::Rc;
 
-use crate::tiles::Tilemap;
+use serde::{Serialize, Deserialize};
 
-use super::super::{Tile, Tiles, tilemap::TILE};
+use crate::tiles::{Tilemap, TileRule, RuleRef, Effect, TileState, TileProps};
 
-const GROWTH_SPEED: usize = 8;
+use super::super::{Tile, Tiles};
 
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Bush {
     pub x: usize,
     pub y: usize,
     pub growth: usize,
-    pub res: Vec<GameObject>,
-    pub current: GameObject,
     pub removed: bool,
     pub frozen: bool,
+    /// steps left before it thaws once no Ice reaches it
+    pub thaw: usize,
+    #[serde(skip)]
+    rule: RuleRef,
 }
 
 impl Bush {
-    pub fn new(x: usize, y: usize, res: Vec<GameObject>) -> Self {
-        let mut current = res[0];
-        current.rect.x = x as f64 * TILE.x;
-        current.rect.y = y as f64 * TILE.y;
+    pub fn new(x: usize, y: usize, rule: Rc<TileRule>) -> Self {
         Self {
             x,
             y,
-            current,
-            res,
             removed: false,
             growth: 0,
             frozen: false,
+            thaw: 0,
+            rule: rule.into(),
         }
     }
 
-    fn spread(&mut self, map: &mut Tilemap) {
-        map.set(self.tile(), self.x as i64 - 1, self.y as i64 - 1, self.tile());
-        map.set(self.tile(), self.x as i64 + 1, self.y as i64 + 1, self.tile());
-    }
-
     fn behaviour(&mut self, map: &mut Tilemap) {
-        if self.frozen {
-            self.current.colour.r = 100;
-            self.current.colour.g = 100;
-            self.current.colour.b = 255;
-            return;
-        }
-        self.spread(map);
+        self.rule.spread(self.tile(), self.x, self.y, map);
     }
 }
 
@@ -55,27 +44,51 @@ impl Tile for Bush {
     }
     
     fn pos(&self) -> (usize, usize) {
-        (self.x as usize, self.y as usize)
+        (self.x, self.y)
+    }
+
+    fn save(&self) -> TileState {
+        TileState::Bush(self.clone())
+    }
+
+    fn set_rule(&mut self, rule: Rc<TileRule>) {
+        self.rule = rule.into();
+    }
+
+    fn init(&mut self, props: &TileProps) {
+        if let Some(g) = props.int("growth") {
+            self.growth = g;
+        }
+        if let Some(f) = props.flag("frozen") {
+            self.frozen = f;
+            self.thaw = self.rule.param("thaw_time");
+        }
+        if let Some(t) = props.int("thaw") {
+            self.thaw = t;
+        }
     }
 
     fn update(&mut self, map: &mut Tilemap) {
-        self.growth += 1;
-        if self.growth >= GROWTH_SPEED {
-            self.growth = 0;
+        if self.rule.thaws(&mut self.frozen, &mut self.thaw) {
+            return;
+        }
+        if self.rule.acts(&mut self.growth) {
             self.behaviour(map);
         }
     }
 
-    fn draw(&self, cam: &mut Camera) {
-        cam.draw(&self.current);
+    fn frozen(&self) -> bool {
+        self.frozen
     }
 
     fn interact(&mut self, other: Tiles) {
-        if other == Tiles::Grass {
-            self.removed = true;
-        }
-        if other == Tiles::Ice {
-            self.frozen = true;
+        match self.rule.effect(other) {
+            Some(Effect::Remove) | Some(Effect::Damage) | Some(Effect::Burn) => self.removed = true,
+            Some(Effect::Freeze) => {
+                self.frozen = true;
+                self.thaw = self.rule.param("thaw_time");
+            },
+            _ => (),
         }
     }
 
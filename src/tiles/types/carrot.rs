@@ -1,61 +1,45 @@
-use sdl_helper::{GameObject, Camera};
+use std::rc::Rc;
 
-use crate::tiles::Tilemap;
+use serde::{Serialize, Deserialize};
 
-use super::super::{Tile, Tiles, tilemap::TILE};
+use crate::tiles::{Tilemap, TileRule, RuleRef, Effect, TileState, TileProps};
 
-const MAX_HP: usize = 3;
-const HARVEST_TIME: usize = 4;
-const WATER_SPREAD: usize = 6;
+use super::super::{Tile, Tiles};
 
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Carrot {
     pub x: usize,
     pub y: usize,
-    res: Vec<GameObject>,
-    current: GameObject,
     hp: usize,
     no_dmg: bool,
     harvestable: bool,
     no_dmg_turns: usize,
     turns_watered: usize,
     frozen: bool,
+    /// steps left before it thaws once no Ice reaches it
+    thaw: usize,
+    /// inside an aura that shelters it from Root
+    sheltered: bool,
+    #[serde(skip)]
+    rule: RuleRef,
 }
 
 impl Carrot {
-    pub fn new(x: usize, y: usize, res: Vec<GameObject>) -> Self {
-        let mut current = res[1];
-        current.rect.x = x as f64 * TILE.x;
-        current.rect.y = y as f64 * TILE.y;
+    pub fn new(x: usize, y: usize, rule: Rc<TileRule>) -> Self {
         Self {
             x,
             y,
-            current,
-            res,
-            hp: MAX_HP,
+            hp: rule.param("max_hp"),
             no_dmg: true,
             harvestable: false,
-            no_dmg_turns: 2,
+            no_dmg_turns: rule.param("start_growth"),
             turns_watered: 0,
             frozen: false,
+            thaw: 0,
+            sheltered: false,
+            rule: rule.into(),
         }
     }
-
-    fn set_current(&mut self) {
-        if self.harvestable {
-            self.current = self.res[0];
-        } else  {
-            self.current = self.res[(MAX_HP + 1) - self.hp];
-        }
-        self.current.rect.x = self.x as f64 * TILE.x;
-        self.current.rect.y = self.y as f64 * TILE.y;
-    }
-
-    fn spread(&mut self, map: &mut Tilemap) {
-        map.set(self.tile(), self.x as i64 + 1, self.y as i64, self.tile());
-        map.set(self.tile(), self.x as i64, self.y as i64 + 1, self.tile());
-        map.set(self.tile(), self.x as i64, self.y as i64 - 1, self.tile());
-        map.set(self.tile(), self.x as i64 - 1, self.y as i64, self.tile());
-    }
 }
 
 
@@ -63,23 +47,50 @@ impl Tile for Carrot {
     fn tile(&self) -> Tiles {
         Tiles::Carrot
     }
-    
+
     fn pos(&self) -> (usize, usize) {
-        (self.x as usize, self.y as usize)
+        (self.x, self.y)
+    }
+
+    fn save(&self) -> TileState {
+        TileState::Carrot(self.clone())
+    }
+
+    fn set_rule(&mut self, rule: Rc<TileRule>) {
+        self.rule = rule.into();
+    }
+
+    fn init(&mut self, props: &TileProps) {
+        if let Some(hp) = props.int("hp") {
+            self.hp = hp.min(self.rule.param("max_hp"));
+        }
+        if let Some(g) = props.int("growth") {
+            self.no_dmg_turns = g;
+        }
+        if let Some(h) = props.flag("harvestable") {
+            self.harvestable = h;
+        }
+        if let Some(f) = props.flag("frozen") {
+            self.frozen = f;
+            self.thaw = self.rule.param("thaw_time");
+        }
+        if let Some(t) = props.int("thaw") {
+            self.thaw = t;
+        }
     }
 
     fn update(&mut self, map: &mut Tilemap) {
-        if self.frozen {
-            self.current.colour.r = 100;
-            self.current.colour.g = 100;
-            self.current.colour.b = 255;
+        self.sheltered = map.aura(Tiles::Root, self.x, self.y) == Some(Effect::Shelter);
+        if self.rule.thaws(&mut self.frozen, &mut self.thaw) {
             return;
         }
+        let max_hp = self.rule.param("max_hp");
         if self.no_dmg {
+            self.harvestable = false;
             self.hp += 1;
-            if self.hp > MAX_HP {
-                self.hp = MAX_HP;
-                if self.no_dmg_turns < HARVEST_TIME {
+            if self.hp > max_hp {
+                self.hp = max_hp;
+                if self.no_dmg_turns < self.rule.param("harvest_time") {
                     self.no_dmg_turns += 1;
                 } else {
                     self.harvestable = true;
@@ -87,11 +98,9 @@ impl Tile for Carrot {
                     self.no_dmg_turns = 0;
                 }
             }
-            self.set_current();
-            self.harvestable = false;
-            if self.turns_watered >= WATER_SPREAD {
+            if self.turns_watered >= self.rule.param("water_spread") {
                 self.turns_watered = 0;
-                self.spread(map);
+                self.rule.spread(self.tile(), self.x, self.y, map);
             }
         } else {
             self.turns_watered = 0;
@@ -99,8 +108,16 @@ impl Tile for Carrot {
         self.no_dmg = true;
     }
 
-    fn draw(&self, cam: &mut Camera) {
-        cam.draw(&self.current);
+    fn frame(&self) -> usize {
+        if self.harvestable {
+            0
+        } else {
+            ((self.rule.param("max_hp") + 1) - self.hp).min(3)
+        }
+    }
+
+    fn frozen(&self) -> bool {
+        self.frozen
     }
 
     fn removed(&mut self) -> bool {
@@ -108,21 +125,20 @@ impl Tile for Carrot {
     }
 
     fn interact(&mut self, other: Tiles) {
-        if (other == Tiles::Root ) && self.hp != 0 {
-            self.hp -= 1;
-            self.no_dmg = false;
-            if self.hp > 0 {
-                self.set_current();
-            }
-        }
-        if other == Tiles::Grass || other == Tiles::Bush {
-            self.hp = 0;
-        }
-        if other == Tiles::Water {
-            self.turns_watered += 1;
-        }
-        if other == Tiles::Ice {
-            self.frozen = true;
+        match self.rule.effect(other) {
+            Some(Effect::Damage) if other == Tiles::Root && self.sheltered => (),
+            Some(Effect::Damage) if self.hp != 0 => {
+                self.hp -= 1;
+                self.no_dmg = false;
+                self.harvestable = false;
+            },
+            Some(Effect::Remove) | Some(Effect::Burn) => self.hp = 0,
+            Some(Effect::Water) => self.turns_watered += 1,
+            Some(Effect::Freeze) => {
+                self.frozen = true;
+                self.thaw = self.rule.param("thaw_time");
+            },
+            _ => (),
         }
     }
 }
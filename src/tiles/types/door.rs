@@ -1,25 +1,19 @@
-use sdl_helper::{GameObject, Camera};
+use serde::{Serialize, Deserialize};
 
-use crate::tiles::{TILE, Tiles};
+use crate::tiles::{Tiles, TileState};
 
 use super::super::Tile;
 
 
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Door {
     pub pos: (usize, usize),
-    res: Vec<GameObject>,
-    current: GameObject
 }
 
 impl Door {
-    pub fn new(x: usize, y: usize, res: Vec<GameObject>) -> Door{
-        let mut current = res[0];
-        current.rect.x = x as f64 * TILE.x;
-        current.rect.y = y as f64 * TILE.y;
+    pub fn new(x: usize, y: usize) -> Door{
         Door {
             pos: (x, y),
-            current,
-            res,
         }
     }
 }
@@ -31,8 +25,8 @@ impl Tile for Door {
     fn pos(&self) -> (usize, usize) {
         self.pos
     }
-    
-    fn draw(&self, cam: &mut Camera) {
-        cam.draw(&self.current);
+
+    fn save(&self) -> TileState {
+        TileState::Door(self.clone())
     }
 }
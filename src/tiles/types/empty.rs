@@ -1,6 +1,10 @@
+use serde::{Serialize, Deserialize};
+
 use super::super::Tile;
+use crate::tiles::TileState;
 
 
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Empty {
     pub pos: (usize, usize),
 }
@@ -17,4 +21,8 @@ impl Tile for Empty {
     fn pos(&self) -> (usize, usize) {
         self.pos
     }
+
+    fn save(&self) -> TileState {
+        TileState::Empty(self.clone())
+    }
 }
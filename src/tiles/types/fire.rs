@@ -0,0 +1,86 @@
+use std::rc::Rc;
+
+use serde::{Serialize, Deserialize};
+
+use crate::tiles::{Tilemap, TileRule, RuleRef, Effect, TileState, TileProps};
+
+use super::super::{Tile, Tiles};
+
+/// Spreads to the tiles its rule targets each time it acts,
+/// burns out after the rule's lifetime unless Water or Ice puts it out first.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fire {
+    pub x: usize,
+    pub y: usize,
+    growth: usize,
+    /// steps it has been burning for, it burns out once this reaches its lifetime
+    burnt: usize,
+    out: bool,
+    #[serde(skip)]
+    rule: RuleRef,
+}
+
+impl Fire {
+    pub fn new(x: usize, y: usize, rule: Rc<TileRule>) -> Self {
+        Self {
+            x, y, growth: 0, burnt: 0, out: false, rule: rule.into(),
+        }
+    }
+}
+
+impl Tile for Fire {
+    fn tile(&self) -> Tiles {
+        Tiles::Fire
+    }
+
+    fn pos(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    fn save(&self) -> TileState {
+        TileState::Fire(self.clone())
+    }
+
+    fn set_rule(&mut self, rule: Rc<TileRule>) {
+        self.rule = rule.into();
+    }
+
+    fn init(&mut self, props: &TileProps) {
+        if let Some(b) = props.int("burnt") {
+            self.burnt = b;
+        }
+    }
+
+    fn update(&mut self, map: &mut Tilemap) {
+        if self.out {
+            return;
+        }
+        self.burnt += 1;
+        if self.rule.acts(&mut self.growth) {
+            self.rule.spread(self.tile(), self.x, self.y, map);
+        }
+    }
+
+    /// flickers between the first two frames, the third once it is about to burn out
+    fn frame(&self) -> usize {
+        if self.rule.lifetime.is_some_and(|l| self.burnt + 1 >= l) {
+            2
+        } else {
+            self.burnt % 2
+        }
+    }
+
+    fn interact(&mut self, other: Tiles) {
+        if self.rule.effect(other) == Some(Effect::Extinguish) {
+            self.out = true;
+        }
+    }
+
+    fn removed(&mut self) -> bool {
+        self.out
+    }
+
+    fn age(&self) -> Option<usize> {
+        Some(self.burnt)
+    }
+}
@@ -1,46 +1,100 @@
-use sdl_helper::{GameObject, Camera};
+use std::rc::Rc;
 
-use crate::tiles::Tilemap;
+use serde::{Serialize, Deserialize};
 
-use super::super::{Tile, Tiles, tilemap::TILE};
+use crate::tiles::{Tilemap, TileRule, RuleRef, Effect, TileState, TileProps};
 
+use super::super::{Tile, Tiles};
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Goat {
     pub x: usize,
     pub y: usize,
-    res: Vec<GameObject>,
-    current: GameObject,
     charges: usize,
-    used_charge: bool,
+    growth: usize,
     sleep: usize,
     frozen: bool,
+    /// steps left before it thaws once no Ice reaches it
+    thaw: usize,
+    /// steps since it last moved
+    move_timer: usize,
+    /// steps between each move, the rule's `move_period` if not set
+    move_period: Option<usize>,
+    /// how far it looks for roots, the rule's `hunt_range` if not set
+    hunt_range: Option<usize>,
+    /// cells to patrol between, set by the player
+    path: Vec<(usize, usize)>,
+    /// index into `path` of the cell it is heading for
+    waypoint: usize,
+    #[serde(skip)]
+    rule: RuleRef,
 }
 
-const MAX_CHARGE: usize = 4;
-const SLEEP_DURATION: usize = 3;
 impl Goat {
-    pub fn new(x: usize, y: usize, res: Vec<GameObject>) -> Self {
-        let mut current = res[0];
-        current.rect.x = x as f64 * TILE.x;
-        current.rect.y = y as f64 * TILE.y;
+    pub fn new(x: usize, y: usize, rule: Rc<TileRule>) -> Self {
         Self {
-            x, y, res, current, charges: MAX_CHARGE, used_charge: false,
-            sleep: 0, frozen: false,
+            x, y, charges: rule.param("max_charge"), growth: 0,
+            sleep: 0, frozen: false, thaw: 0, move_timer: 0,
+            move_period: None, hunt_range: None, path: Vec::new(), waypoint: 0, rule: rule.into(),
         }
     }
 
-    fn set_tile(&mut self, x: i64, y: i64, map: &mut Tilemap) {
-        let t = map.get_or_none(x, y);
-        if t == Tiles::Root || t == Tiles::Carrot {
-            map.set(self.tile(), x, y, Tiles::Grass);
-            self.used_charge = true;
+    /// Goats walk over Grass and eat any Root they walk onto.
+    fn walkable(t: Tiles) -> bool {
+        t == Tiles::Grass || t == Tiles::Root
+    }
+
+    /// Every `move_period` steps take one step along the patrol path if there is one,
+    /// otherwise towards the nearest Root within `hunt_range`, or with no range to
+    /// the neighbouring cell with the most Root around it.
+    fn walk(&mut self, map: &mut Tilemap) {
+        let period = self.move_period.unwrap_or(self.rule.param("move_period"));
+        if period == 0 {
+            return;
+        }
+        self.move_timer += 1;
+        if self.move_timer < period {
+            return;
+        }
+        self.move_timer = 0;
+        let pos = self.pos();
+        let range = self.hunt_range.unwrap_or(self.rule.param("hunt_range"));
+        let next = if !self.path.is_empty() {
+            if self.path[self.waypoint % self.path.len()] == pos {
+                self.waypoint = (self.waypoint + 1) % self.path.len();
+            }
+            let target = self.path[self.waypoint % self.path.len()];
+            map.step_towards(self.tile(), pos, map.w * map.h, Self::walkable, |x, y| (x, y) == target)
+        } else if range > 0 {
+            map.step_towards(self.tile(), pos, range, Self::walkable, |x, y| map.get(x, y) == Tiles::Root)
+        } else {
+            most_roots(map, pos)
+        };
+        if let Some(to) = next {
+            map.move_tile(self.tile(), pos, to);
         }
     }
+}
 
-    fn set_current(&mut self) {
-        self.current = self.res[MAX_CHARGE - self.charges];
-        self.current.rect.x = self.x as f64 * TILE.x;
-        self.current.rect.y = self.y as f64 * TILE.y;
+/// The neighbour of `pos` a goat can walk onto with the most Root in and
+/// around it, `None` if there are no roots next to any of them.
+fn most_roots(map: &Tilemap, pos: (usize, usize)) -> Option<(usize, usize)> {
+    let roots = |x: i64, y: i64| (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+        .filter(|(dx, dy)| map.get_or_none(x + dx, y + dy) == Tiles::Root).count();
+    let mut best = None;
+    let mut most = 0;
+    for (dx, dy) in [(0, -1), (1, 0), (0, 1), (-1, 0)] {
+        let (x, y) = (pos.0 as i64 + dx, pos.1 as i64 + dy);
+        if !Goat::walkable(map.get_or_none(x, y)) || map.blocks(Tiles::Goat, x as usize, y as usize) {
+            continue;
+        }
+        let n = roots(x, y);
+        if n > most {
+            most = n;
+            best = Some((x as usize, y as usize));
+        }
     }
+    best
 }
 
 
@@ -50,49 +104,94 @@ impl Tile for Goat {
     }
     
     fn pos(&self) -> (usize, usize) {
-        (self.x as usize, self.y as usize)
+        (self.x, self.y)
+    }
+
+    fn set_pos(&mut self, x: usize, y: usize) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn movable(&self) -> bool {
+        true
+    }
+
+    fn set_path(&mut self, path: Vec<(usize, usize)>) {
+        self.path = path;
+        self.waypoint = 0;
+    }
+
+    fn path(&self) -> &[(usize, usize)] {
+        &self.path
+    }
+
+    fn save(&self) -> TileState {
+        TileState::Goat(self.clone())
+    }
+
+    fn set_rule(&mut self, rule: Rc<TileRule>) {
+        self.rule = rule.into();
+    }
+
+    fn init(&mut self, props: &TileProps) {
+        if let Some(c) = props.int("charges") {
+            self.charges = c.min(self.rule.param("max_charge"));
+        }
+        if let Some(s) = props.int("sleep") {
+            self.sleep = s;
+        }
+        if let Some(f) = props.flag("frozen") {
+            self.frozen = f;
+            self.thaw = self.rule.param("thaw_time");
+        }
+        if let Some(t) = props.int("thaw") {
+            self.thaw = t;
+        }
+        if let Some(p) = props.int("move_period") {
+            self.move_period = Some(p);
+        }
+        if let Some(r) = props.int("hunt_range") {
+            self.hunt_range = Some(r);
+        }
     }
 
     fn update(&mut self, map: &mut Tilemap) {
-        if self.frozen {
-            self.current.colour.r = 100;
-            self.current.colour.g = 100;
-            self.current.colour.b = 255;
+        if self.rule.thaws(&mut self.frozen, &mut self.thaw) {
             return;
         }
+        let max_charge = self.rule.param("max_charge");
+        let sleep_duration = self.rule.param("sleep_duration").max(1);
         if self.sleep > 0 {
             self.sleep -= 1;
-            if self.sleep % SLEEP_DURATION == 0 {
-                self.charges += 1;
-                self.set_current();
+            if self.sleep.is_multiple_of(sleep_duration) {
+                self.charges = (self.charges + 1).min(max_charge);
             }
             return;
         }
-        self.used_charge = false;
-        self.set_tile(self.x as i64, self.y as i64 + 1, map);
-        self.set_tile(self.x as i64, self.y as i64 - 1, map);
-        self.set_tile(self.x as i64 + 1, self.y as i64, map);
-        self.set_tile(self.x as i64 - 1, self.y as i64, map);
-        self.set_tile(self.x as i64 + 1, self.y as i64 + 1, map);
-        self.set_tile(self.x as i64 - 1, self.y as i64 + 1, map);
-        self.set_tile(self.x as i64 + 1, self.y as i64 - 1, map);
-        self.set_tile(self.x as i64 - 1, self.y as i64 - 1, map);
-        if self.charges > 0 && self.used_charge {
-            self.charges -= 1;
-            self.set_current();
-        } else if self.charges == 0 {
-            self.sleep = MAX_CHARGE * SLEEP_DURATION;
+        if self.rule.acts(&mut self.growth) {
+            let used_charge = self.rule.spread(self.tile(), self.x, self.y, map) > 0;
+            if self.charges > 0 && used_charge {
+                self.charges -= 1;
+            } else if self.charges == 0 {
+                self.sleep = max_charge * sleep_duration;
+                return;
+            }
         }
+        self.walk(map);
+    }
+
+    fn frame(&self) -> usize {
+        self.rule.param("max_charge").saturating_sub(self.charges).min(4)
     }
 
-    fn draw(&self, cam: &mut Camera) {
-        cam.draw(&self.current);
+    fn frozen(&self) -> bool {
+        self.frozen
     }
 
     fn interact(&mut self, other: Tiles) {
-        if other == Tiles::Ice {
+        if self.rule.effect(other) == Some(Effect::Freeze) {
             self.frozen = true;
+            self.thaw = self.rule.param("thaw_time");
         }
     }
 }
-
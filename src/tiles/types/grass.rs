@@ -1,6 +1,9 @@
-use crate::tiles::Tiles;
+use serde::{Serialize, Deserialize};
+
+use crate::tiles::{Tiles, TileState};
 use crate::tiles::Tile;
 
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Grass {
     pub x: usize,
     pub y: usize,
@@ -19,4 +22,8 @@ impl Tile for Grass {
     fn pos(&self) -> (usize, usize) {
         (self.x, self.y)
     }
+
+    fn save(&self) -> TileState {
+        TileState::Grass(self.clone())
+    }
 }
@@ -1,29 +1,27 @@
-use sdl_helper::{GameObject, Camera};
+use std::rc::Rc;
 
-use crate::tiles::Tilemap;
+use serde::{Serialize, Deserialize};
 
-use super::super::{Tile, Tiles, tilemap::TILE};
+use crate::tiles::{Tilemap, TileRule, RuleRef, Effect, TileState};
 
+use super::super::{Tile, Tiles};
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Ice {
     pub x: usize,
     pub y: usize,
-    _res: Vec<GameObject>,
-    current: GameObject,
+    growth: usize,
+    melted: bool,
+    #[serde(skip)]
+    rule: RuleRef,
 }
 
 impl Ice {
-    pub fn new(x: usize, y: usize, res: Vec<GameObject>) -> Self {
-        let mut current = res[0];
-        current.rect.x = x as f64 * TILE.x;
-        current.rect.y = y as f64 * TILE.y;
+    pub fn new(x: usize, y: usize, rule: Rc<TileRule>) -> Self {
         Self {
-            x, y, _res: res, current,
+            x, y, growth: 0, melted: false, rule: rule.into(),
         }
     }
-
-    fn set_tile(&mut self, x: i64, y: i64, map: &mut Tilemap) {
-        map.set(self.tile(), x, y, self.tile());
-    }
 }
 
 
@@ -33,21 +31,30 @@ impl Tile for Ice {
     }
     
     fn pos(&self) -> (usize, usize) {
-        (self.x as usize, self.y as usize)
+        (self.x, self.y)
+    }
+
+    fn save(&self) -> TileState {
+        TileState::Ice(self.clone())
+    }
+
+    fn set_rule(&mut self, rule: Rc<TileRule>) {
+        self.rule = rule.into();
     }
 
     fn update(&mut self, map: &mut Tilemap) {
-        self.set_tile(self.x as i64, self.y as i64 + 1, map);
-        self.set_tile(self.x as i64, self.y as i64 - 1, map);
-        self.set_tile(self.x as i64 + 1, self.y as i64, map);
-        self.set_tile(self.x as i64 - 1, self.y as i64, map);
-        self.set_tile(self.x as i64 + 1, self.y as i64 + 1, map);
-        self.set_tile(self.x as i64 - 1, self.y as i64 + 1, map);
-        self.set_tile(self.x as i64 + 1, self.y as i64 - 1, map);
-        self.set_tile(self.x as i64 - 1, self.y as i64 - 1, map);
-    }
-
-    fn draw(&self, cam: &mut Camera) {
-        cam.draw(&self.current);
+        if self.rule.acts(&mut self.growth) {
+            self.rule.spread(self.tile(), self.x, self.y, map);
+        }
+    }
+
+    fn interact(&mut self, other: Tiles) {
+        if self.rule.effect(other) == Some(Effect::Melt) {
+            self.melted = true;
+        }
+    }
+
+    fn removed(&mut self) -> bool {
+        self.melted
     }
 }
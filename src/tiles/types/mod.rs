@@ -7,6 +7,9 @@ mod bush;
 mod water;
 mod door;
 mod ice;
+mod fire;
+mod ash;
+mod scarecrow;
 
 pub use empty::Empty;
 pub use root::Root;
@@ -17,3 +20,6 @@ pub use bush::Bush;
 pub use water::Water;
 pub use door::Door;
 pub use ice::Ice;
+pub use fire::Fire;
+pub use ash::Ash;
+pub use scarecrow::Scarecrow;
@@ -1,64 +1,46 @@
-use sdl_helper::{GameObject, Camera};
+use std::rc::Rc;
 
-use crate::tiles::Tilemap;
+use serde::{Serialize, Deserialize};
 
-use super::super::{Tile, Tiles, tilemap::TILE};
+use crate::tiles::{Tilemap, TileRule, RuleRef, Effect, TileState, TileProps};
 
-const GROWTH_SPEED: usize = 2;
+use super::super::{Tile, Tiles};
 
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Root {
     pub x: usize,
     pub y: usize,
     pub growth: usize,
     pub age: usize,
     pub max_age: usize,
-    pub res: Vec<GameObject>,
-    pub current: GameObject,
     pub removed: bool,
     pub frozen: bool,
+    /// steps left before it thaws once no Ice reaches it
+    pub thaw: usize,
+    #[serde(skip)]
+    rule: RuleRef,
 }
 
 impl Root {
-    pub fn new(x: usize, y: usize, res: Vec<GameObject>) -> Self {
-        let mut current = res[0];
-        let max_age = res.len() - 1;
-        current.rect.x = x as f64 * TILE.x;
-        current.rect.y = y as f64 * TILE.y;
+    pub fn new(x: usize, y: usize, rule: Rc<TileRule>) -> Self {
         Self {
             x,
             y,
-            current,
-            res,
             age: 0,
-            max_age,
+            max_age: rule.param("max_age"),
             removed: false,
             growth: 0,
             frozen: false,
+            thaw: 0,
+            rule: rule.into(),
         }
     }
 
-    fn spread(&mut self, map: &mut Tilemap) {
-        map.set(self.tile(), self.x as i64 + 1, self.y as i64, self.tile());
-        map.set(self.tile(), self.x as i64, self.y as i64 + 1, self.tile());
-        map.set(self.tile(), self.x as i64, self.y as i64 - 1, self.tile());
-        map.set(self.tile(), self.x as i64 - 1, self.y as i64, self.tile());
-    }
-
     fn behaviour(&mut self, map: &mut Tilemap) {
-        if self.frozen {
-            self.current.colour.r = 100;
-            self.current.colour.g = 100;
-            self.current.colour.b = 255;
-            return;
-        }
-        
-        if self.age != self.max_age{
+        if self.age < self.max_age {
             self.age += 1;
-                self.current = self.res[self.age];
-            self.current.rect.x = self.x as f64 * TILE.x;
-            self.current.rect.y = self.y as f64 * TILE.y;
-        } else if !self.frozen {
-            self.spread(map);
+        } else {
+            self.rule.spread(self.tile(), self.x, self.y, map);
         }
     }
 }
@@ -68,29 +50,63 @@ impl Tile for Root {
     fn tile(&self) -> Tiles {
         Tiles::Root
     }
-    
+
     fn pos(&self) -> (usize, usize) {
-        (self.x as usize, self.y as usize)
+        (self.x, self.y)
+    }
+
+    fn save(&self) -> TileState {
+        TileState::Root(self.clone())
+    }
+
+    fn set_rule(&mut self, rule: Rc<TileRule>) {
+        self.rule = rule.into();
+    }
+
+    fn init(&mut self, props: &TileProps) {
+        if let Some(m) = props.int("max_age") {
+            self.max_age = m;
+        }
+        if let Some(a) = props.int("age") {
+            self.age = a.min(self.max_age);
+        }
+        if props.flag("mature") == Some(true) {
+            self.age = self.max_age;
+        }
+        if let Some(f) = props.flag("frozen") {
+            self.frozen = f;
+            self.thaw = self.rule.param("thaw_time");
+        }
+        if let Some(t) = props.int("thaw") {
+            self.thaw = t;
+        }
     }
 
     fn update(&mut self, map: &mut Tilemap) {
-        self.growth += 1;
-        if self.growth >= GROWTH_SPEED {
-            self.growth = 0;
+        if self.rule.thaws(&mut self.frozen, &mut self.thaw) {
+            return;
+        }
+        if self.rule.acts(&mut self.growth) {
             self.behaviour(map);
         }
     }
 
-    fn draw(&self, cam: &mut Camera) {
-        cam.draw(&self.current);
+    fn frame(&self) -> usize {
+        self.age.min(3)
+    }
+
+    fn frozen(&self) -> bool {
+        self.frozen
     }
 
     fn interact(&mut self, other: Tiles) {
-        if other == Tiles::Grass || other == Tiles::Bush {
-            self.removed = true;
-        }
-        if other == Tiles::Ice {
-            self.frozen = true;
+        match self.rule.effect(other) {
+            Some(Effect::Remove) | Some(Effect::Damage) | Some(Effect::Burn) => self.removed = true,
+            Some(Effect::Freeze) => {
+                self.frozen = true;
+                self.thaw = self.rule.param("thaw_time");
+            },
+            _ => (),
         }
     }
 
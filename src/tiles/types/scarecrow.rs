@@ -0,0 +1,31 @@
+use serde::{Serialize, Deserialize};
+
+use crate::tiles::{Tiles, TileState};
+use crate::tiles::Tile;
+
+/// Keeps pests away from the cells around it, what it keeps away
+/// is set by its rule's interactions and "radius".
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scarecrow {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Scarecrow {
+    pub fn new(x: usize, y: usize) -> Self {
+        Scarecrow {x, y}
+    }
+}
+
+impl Tile for Scarecrow {
+    fn tile(&self) -> Tiles {
+        Tiles::Scarecrow
+    }
+    fn pos(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    fn save(&self) -> TileState {
+        TileState::Scarecrow(self.clone())
+    }
+}
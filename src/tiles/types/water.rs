@@ -1,31 +1,27 @@
-use sdl_helper::{GameObject, Camera};
+use std::rc::Rc;
 
-use crate::tiles::Tilemap;
+use serde::{Serialize, Deserialize};
 
-use super::super::{Tile, Tiles, tilemap::TILE};
+use crate::tiles::{Tilemap, TileRule, RuleRef, Effect, TileState, TileProps};
 
+use super::super::{Tile, Tiles};
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Water {
     pub x: usize,
     pub y: usize,
-    _res: Vec<GameObject>,
-    current: GameObject,
+    growth: usize,
     frozen: bool,
+    /// steps left before it thaws once no Ice reaches it
+    thaw: usize,
+    #[serde(skip)]
+    rule: RuleRef,
 }
 
 impl Water {
-    pub fn new(x: usize, y: usize, res: Vec<GameObject>) -> Self {
-        let mut current = res[0];
-        current.rect.x = x as f64 * TILE.x;
-        current.rect.y = y as f64 * TILE.y;
+    pub fn new(x: usize, y: usize, rule: Rc<TileRule>) -> Self {
         Self {
-            x, y, _res: res, current, frozen: false,
-        }
-    }
-
-    fn set_tile(&mut self, x: i64, y: i64, map: &mut Tilemap) {
-        let t = map.get_or_none(x, y);
-        if t == Tiles::Carrot {
-            map.set(self.tile(), x, y, self.tile());
+            x, y, growth: 0, frozen: false, thaw: 0, rule: rule.into(),
         }
     }
 }
@@ -37,34 +33,48 @@ impl Tile for Water {
     }
     
     fn pos(&self) -> (usize, usize) {
-        (self.x as usize, self.y as usize)
+        (self.x, self.y)
+    }
+
+    fn save(&self) -> TileState {
+        TileState::Water(self.clone())
+    }
+
+    fn set_rule(&mut self, rule: Rc<TileRule>) {
+        self.rule = rule.into();
+    }
+
+    fn init(&mut self, props: &TileProps) {
+        if let Some(f) = props.flag("frozen") {
+            self.frozen = f;
+            self.thaw = self.rule.param("thaw_time");
+        }
+        if let Some(t) = props.int("thaw") {
+            self.thaw = t;
+        }
     }
 
     fn update(&mut self, map: &mut Tilemap) {
-        if self.frozen {
-            self.current.colour.r = 100;
-            self.current.colour.g = 100;
-            self.current.colour.b = 255;
+        if self.rule.thaws(&mut self.frozen, &mut self.thaw) {
             return;
         }
-        self.set_tile(self.x as i64, self.y as i64 + 1, map);
-        self.set_tile(self.x as i64, self.y as i64 - 1, map);
-        self.set_tile(self.x as i64 + 1, self.y as i64, map);
-        self.set_tile(self.x as i64 - 1, self.y as i64, map);
-        self.set_tile(self.x as i64 + 1, self.y as i64 + 1, map);
-        self.set_tile(self.x as i64 - 1, self.y as i64 + 1, map);
-        self.set_tile(self.x as i64 + 1, self.y as i64 - 1, map);
-        self.set_tile(self.x as i64 - 1, self.y as i64 - 1, map);
+        if self.rule.acts(&mut self.growth) {
+            self.rule.spread(self.tile(), self.x, self.y, map);
+        }
     }
 
-    fn draw(&self, cam: &mut Camera) {
-        cam.draw(&self.current);
+    fn frozen(&self) -> bool {
+        self.frozen
     }
 
     fn interact(&mut self, other: Tiles) {
-        if other == Tiles::Ice {
-            self.frozen = true;
+        match self.rule.effect(other) {
+            Some(Effect::Freeze) => {
+                self.frozen = true;
+                self.thaw = self.rule.param("thaw_time");
+            },
+            Some(Effect::Melt) => self.frozen = false,
+            _ => (),
         }
     }
 }
-
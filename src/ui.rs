@@ -1,22 +1,71 @@
 use std::path::Path;
 
-use sdl_helper::{Render, input::Controls, Camera, Error, GameObject, resource::Texture, geometry::Vec2};
-
-use crate::tiles::{Tilemap, TILE, Tiles};
+use sdl_helper::{Render, input::Controls, Camera, Error, GameObject, Colour, resource::Texture, geometry::{Vec2, Rect}};
+use sdl_helper::input::keyboard::{Key, MouseButton};
 
+use crate::catalogue::Purchases;
+use crate::error::GameError;
+use crate::inventory::Inventory;
+use crate::objective::{ObjectiveProgress, Kind, Status};
+use crate::tiles::{TileSprites, TILE, Tiles};
+use crate::replay::{Replay, Action};
 
+/// How long an error stays on screen, in seconds.
+const ERROR_TIME: f64 = 4.0;
+/// Characters that fit on one line of an error message.
+const ERROR_WIDTH: usize = 56;
+const ERROR_LINES: usize = 3;
 
 pub struct Ui {
     money: usize,
     money_icon: GameObject,
     money_tex: Vec<GameObject>,
     tiles: Texture,
+    letters: Texture,
     changed_money: bool,
-    tile_to_set: Tiles,
+    inventory: Inventory,
+    /// the inventory the hotbar was last drawn for
+    hotbar: Inventory,
+    hotbar_tex: Vec<GameObject>,
+    hotbar_slots: Vec<Rect>,
+    hotbar_clicked: bool,
+    /// what clicking the board does
+    tool: Tool,
+    sell_cursor: GameObject,
+    /// what has been bought from the shop this level
+    purchases: Purchases,
     current_cursor: GameObject,
     cursor: GameObject,
+    replay: Option<Replay>,
+    error: Vec<GameObject>,
+    error_time: f64,
+    turn_icon: GameObject,
+    objectives: Vec<ObjectiveProgress>,
+    objective_tex: Vec<GameObject>,
+}
+
+/// What clicking on the board does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tool {
+    /// place the active tile from the inventory
+    Place,
+    /// sell the tile clicked, toggled with X
+    Sell,
+    /// pick a goat then the cells for it to patrol, toggled with P
+    Patrol,
 }
 
+const OBJECTIVE_POS: Vec2 = Vec2::new(10.0, 60.0);
+const OBJECTIVE_ROW: f64 = 22.0;
+const HOTBAR_POS: Vec2 = Vec2::new(130.0, 4.0);
+/// distance between hotbar slots, room for an icon and its count
+const HOTBAR_STEP: f64 = TILE.x * 2.0;
+/// keys that pick each hotbar slot
+const HOTBAR_KEYS: [Key; 9] = [
+    Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5,
+    Key::Num6, Key::Num7, Key::Num8, Key::Num9,
+];
+
 impl Ui {
     pub fn new(render: &mut Render) -> Result<Ui, Error> {
         let mut money = GameObject::new_from_tex(
@@ -33,39 +82,88 @@ impl Ui {
         money.rect.y = 0.0;
         money.parallax = Vec2::new(0.0, 0.0);
         Ok(Ui {
-            money: 10,
+            money: 0,
             money_icon: money,
             money_tex: Vec::new(),
             tiles: render.texture_manager.load(
                 Path::new("resources/textures/tiles/game_tiles.png")
             )?,
+            letters: render.texture_manager.load(
+                Path::new("resources/textures/letters.png")
+            )?,
             changed_money: true,
-            tile_to_set: Tiles::None,
+            inventory: Inventory::new(),
+            hotbar: Inventory::new(),
+            hotbar_tex: Vec::new(),
+            hotbar_slots: Vec::new(),
+            hotbar_clicked: false,
+            tool: Tool::Place,
+            sell_cursor: GameObject::new_from_tex(
+                render.texture_manager.load(
+                    Path::new("resources/textures/lock_cursor.png")
+                )?
+            ),
+            purchases: Purchases::new(),
             current_cursor: cursor,
             cursor,
+            replay: None,
+            error: Vec::new(),
+            error_time: 0.0,
+            turn_icon: GameObject::new_from_tex(
+                render.texture_manager.load(
+                    Path::new("resources/textures/btn/next.png")
+                )?
+            ),
+            objectives: Vec::new(),
+            objective_tex: Vec::new(),
         })
     }
 
     pub fn update(&mut self, controls: &Controls) {
-        if self.tile_to_set != Tiles::None {
-            self.current_cursor = Tilemap::get_tile(self.tiles, 0, match self.tile_to_set {
-                Tiles::Goat => 4,
-                Tiles::Water => 5,
-                Tiles::Bush => 6,
-                Tiles::Ice => 7,
-                Tiles::Key => 8,
-                _ => panic!("not able to buy that tile"),
-
-            });
-        } else {
-            self.current_cursor = self.cursor;
+        self.hotbar_clicked = false;
+        for (i, k) in HOTBAR_KEYS.iter().enumerate() {
+            if controls.kbm.press(*k) && self.inventory.select_slot(i) {
+                self.tool = Tool::Place;
+            }
+        }
+        if controls.kbm.mouse_press(MouseButton::Left) {
+            let mouse = controls.kbm.mouse_pos_cam_off();
+            if let Some(i) = self.hotbar_slots.iter().position(|r| r.contains(&mouse)) {
+                self.inventory.select_slot(i);
+                self.tool = Tool::Place;
+                self.hotbar_clicked = true;
+            }
+        }
+        for (k, tool) in [(Key::X, Tool::Sell), (Key::P, Tool::Patrol)] {
+            if controls.kbm.press(k) {
+                self.tool = if self.tool == tool { Tool::Place } else { tool };
+            }
+        }
+        if self.hotbar != self.inventory {
+            self.set_hotbar();
         }
+        self.current_cursor = match (self.tool, cursor_sprite(self.tiles, self.inventory.active())) {
+            (Tool::Sell, _) => self.sell_cursor,
+            (Tool::Patrol, _) => {
+                let mut c = TileSprites::get_tile(self.tiles, 0, 4);
+                c.colour = Colour::new(150, 150, 255, 200);
+                c
+            },
+            (Tool::Place, Some(c)) => c,
+            (Tool::Place, None) => self.cursor,
+        };
         self.current_cursor.rect.x = controls.kbm.mouse_pos().x;
         self.current_cursor.rect.y = controls.kbm.mouse_pos().y;
         if self.changed_money {
             self.changed_money = false;
             self.money_tex = self.get_nums(self.money, self.money_icon.rect.centre());
         }
+        if self.error_time > 0.0 {
+            self.error_time -= controls.frame_elapsed;
+            if self.error_time <= 0.0 {
+                self.error.clear();
+            }
+        }
     }
 
     pub fn draw(&self, cam: &mut Camera) {
@@ -73,15 +171,122 @@ impl Ui {
         for m in self.money_tex.iter() {
             cam.draw(m);
         }
+        for e in self.error.iter() {
+            cam.draw(e);
+        }
+        for o in self.objective_tex.iter() {
+            cam.draw(o);
+        }
+        for h in self.hotbar_tex.iter() {
+            cam.draw(h);
+        }
         cam.draw(&self.current_cursor);
     }
 
+    /// Show `e` along the bottom of the screen for a few seconds,
+    /// it is also printed in case it is too long to fit.
+    pub fn show_error(&mut self, e: &GameError) {
+        eprintln!("{}", e);
+        let mut lines: Vec<String> = vec![String::new()];
+        for word in e.to_string().split_whitespace() {
+            let line = lines.last_mut().unwrap();
+            if !line.is_empty() && line.len() + word.len() + 1 > ERROR_WIDTH {
+                lines.push(String::new());
+            }
+            let line = lines.last_mut().unwrap();
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.truncate(ERROR_LINES);
+        self.error.clear();
+        let bottom = 320.0 - TILE.y * (lines.len() as f64 + 0.5);
+        for (i, line) in lines.iter().enumerate() {
+            let text = self.get_text(line, Vec2::new(10.0, bottom + TILE.y * i as f64));
+            self.error.extend(text);
+        }
+        self.error_time = ERROR_TIME;
+    }
+
+    /// Show how far along each of the level's objectives is,
+    /// an icon then the current and target numbers.
+    pub fn set_objectives(&mut self, objectives: &[ObjectiveProgress]) {
+        if self.objectives == objectives {
+            return;
+        }
+        self.objectives = objectives.to_vec();
+        self.objective_tex.clear();
+        for (i, o) in objectives.iter().enumerate() {
+            let pos = OBJECTIVE_POS + Vec2::new(0.0, i as f64 * OBJECTIVE_ROW);
+            let mut icon = match o.kind {
+                Kind::OpenDoor => TileSprites::get_tile(self.tiles, 2, 2),
+                Kind::Harvest => TileSprites::get_tile(self.tiles, 0, 0),
+                Kind::KeepCarrots => TileSprites::get_tile(self.tiles, 3, 0),
+                Kind::ClearRoots => TileSprites::get_tile(self.tiles, 3, 1),
+                Kind::Money => self.money_icon,
+                Kind::Survive | Kind::TurnLimit => self.turn_icon,
+            };
+            icon.rect.x = pos.x;
+            icon.rect.y = pos.y;
+            icon.rect.w = TILE.x;
+            icon.rect.h = TILE.y;
+            icon.parallax = Vec2::new(0.0, 0.0);
+            icon.colour = match o.status {
+                Status::Met => Colour::new(150, 255, 150, 255),
+                Status::Failed => Colour::new(255, 120, 120, 255),
+                Status::Pending => Colour::white(),
+            };
+            self.objective_tex.push(icon);
+            let current = self.get_nums(o.current, pos + Vec2::new(TILE.x * 2.0, 0.0));
+            let mut target = self.get_nums(o.target, pos + Vec2::new(TILE.x * 4.0, 0.0));
+            for t in target.iter_mut() {
+                t.colour = Colour::new(150, 150, 150, 255);
+            }
+            self.objective_tex.extend(current);
+            self.objective_tex.extend(target);
+        }
+    }
+
+    /// A slot for each stack in the inventory, its icon and how many are held,
+    /// with every stack but the active one greyed out.
+    fn set_hotbar(&mut self) {
+        self.hotbar = self.inventory.clone();
+        self.hotbar_tex.clear();
+        self.hotbar_slots.clear();
+        let stacks = self.inventory.stacks().to_vec();
+        for (i, (tile, count)) in stacks.into_iter().enumerate() {
+            let pos = HOTBAR_POS + Vec2::new(i as f64 * HOTBAR_STEP, 0.0);
+            let mut icon = match cursor_sprite(self.tiles, tile) {
+                Some(c) => c,
+                None => continue,
+            };
+            icon.rect.x = pos.x;
+            icon.rect.y = pos.y;
+            icon.rect.w = TILE.x;
+            icon.rect.h = TILE.y;
+            icon.parallax = Vec2::new(0.0, 0.0);
+            let colour = if tile == self.inventory.active() {
+                Colour::white()
+            } else {
+                Colour::new(150, 150, 150, 255)
+            };
+            icon.colour = colour;
+            self.hotbar_slots.push(icon.rect);
+            self.hotbar_tex.push(icon);
+            for mut n in self.get_nums(count, pos + Vec2::new(TILE.x, 0.0)) {
+                n.colour = colour;
+                self.hotbar_tex.push(n);
+            }
+        }
+    }
+
     fn get_digit(&mut self, num: usize, pos: Vec2, place: f64) -> GameObject {
-        let mut t = Tilemap::get_tile(self.tiles, num, 3);
+        let mut t = TileSprites::get_tile(self.tiles, num, 3);
         t.parallax = Vec2::new(0.0, 0.0);
         t.rect.x = pos.x - TILE.x * 0.5 * place;
         t.rect.y = pos.y;
-        return t;
+        t
     }
 
     pub fn get_nums(&mut self, num: usize, pos: Vec2) -> Vec<GameObject> {
@@ -103,6 +308,24 @@ impl Ui {
         n
     }
 
+    /// Sprites spelling out `text` from `pos` rightwards, letters are drawn
+    /// in upper case and anything but letters and digits is left as a gap.
+    pub fn get_text(&mut self, text: &str, pos: Vec2) -> Vec<GameObject> {
+        let mut t = Vec::new();
+        for (i, c) in text.chars().enumerate() {
+            let mut g = match c.to_ascii_uppercase() {
+                c @ 'A'..='Z' => TileSprites::get_tile(self.letters, c as usize - 'A' as usize, 0),
+                c @ '0'..='9' => TileSprites::get_tile(self.tiles, c as usize - '0' as usize, 3),
+                _ => continue,
+            };
+            g.parallax = Vec2::new(0.0, 0.0);
+            g.rect.x = pos.x + TILE.x * 0.5 * i as f64;
+            g.rect.y = pos.y;
+            t.push(g);
+        }
+        t
+    }
+
     pub fn get_money(&self) -> usize {
         self.money
     }
@@ -117,18 +340,107 @@ impl Ui {
         self.changed_money = true;
     }
 
-    pub fn set_tile(&mut self, t: Tiles) {
-        self.tile_to_set = t;
+    /// Add a `t` to the inventory. Only tiles from the shop can be held.
+    pub fn add_tile(&mut self, t: Tiles) -> Result<(), GameError> {
+        if cursor_frame(t).is_none() {
+            return Err(GameError::NotForSale(t));
+        }
+        self.inventory.add(t);
+        Ok(())
     }
 
-    pub fn get_tile(&mut self) -> Tiles {
-        self.tile_to_set
+    /// Use up one `t` from the inventory, false if there wasn't one.
+    pub fn take_tile(&mut self, t: Tiles) -> bool {
+        self.inventory.take(t)
     }
 
-    pub fn pop_tile(&mut self) -> Tiles {
-        let t = self.tile_to_set;
-        self.tile_to_set = Tiles::None;
-        t
+    /// The tile placed when the board is clicked, `Tiles::None` if nothing is held.
+    pub fn active_tile(&self) -> Tiles {
+        self.inventory.active()
+    }
+
+    pub fn inventory(&self) -> &Inventory {
+        &self.inventory
+    }
+
+    pub fn set_inventory(&mut self, inventory: Inventory) -> Result<(), GameError> {
+        if let Some((t, _)) = inventory.stacks().iter().find(|(t, _)| cursor_frame(*t).is_none()) {
+            return Err(GameError::NotForSale(*t));
+        }
+        self.inventory = inventory;
+        Ok(())
+    }
+
+    pub fn clear_inventory(&mut self) {
+        self.inventory = Inventory::new();
+        self.tool = Tool::Place;
+    }
+
+    pub fn tool(&self) -> Tool {
+        self.tool
+    }
+
+    /// True if a hotbar slot was clicked this frame.
+    pub fn hotbar_clicked(&self) -> bool {
+        self.hotbar_clicked
+    }
+
+    pub fn purchases(&self) -> &Purchases {
+        &self.purchases
+    }
+
+    pub fn purchases_mut(&mut self) -> &mut Purchases {
+        &mut self.purchases
+    }
+
+    pub fn set_purchases(&mut self, purchases: Purchases) {
+        self.purchases = purchases;
+    }
+
+    /// Start recording the player's actions on a freshly loaded map.
+    pub fn start_recording(&mut self, map: &Path) {
+        self.replay = Some(Replay::new(map.to_path_buf(), self.money));
+    }
+
+    /// Keep adding to a replay that has already been played back.
+    pub fn resume_recording(&mut self, replay: Replay) {
+        self.replay = Some(replay);
+    }
+
+    pub fn stop_recording(&mut self) -> Option<Replay> {
+        self.replay.take()
+    }
+
+    pub fn record(&mut self, action: Action) {
+        if let Some(r) = &mut self.replay {
+            r.record(action);
+        }
     }
     
 }
+
+/// Row of `game_tiles.png` drawn under the cursor while holding `tile`,
+/// and how many tiles across and down its sprite is.
+fn cursor_frame(tile: Tiles) -> Option<(usize, usize, usize)> {
+    match tile {
+        Tiles::Goat => Some((4, 1, 1)),
+        Tiles::Water => Some((5, 1, 1)),
+        Tiles::Bush => Some((6, 1, 1)),
+        Tiles::Ice => Some((7, 1, 1)),
+        Tiles::Key => Some((8, 1, 1)),
+        Tiles::Fire => Some((9, 1, 1)),
+        Tiles::Greenhouse => Some((11, 2, 2)),
+        Tiles::Fence => Some((13, 2, 1)),
+        Tiles::Scarecrow => Some((14, 1, 1)),
+        _ => None,
+    }
+}
+
+/// The sprite for holding `tile`, shrunk to a single tile.
+fn cursor_sprite(tiles: Texture, tile: Tiles) -> Option<GameObject> {
+    let (f, w, h) = cursor_frame(tile)?;
+    let mut c = TileSprites::get_sprite(tiles, 0, f, w, h);
+    c.rect.w = TILE.x;
+    c.rect.h = TILE.y;
+    Some(c)
+}
@@ -0,0 +1,205 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::Path;
+
+use crate::catalogue::Catalogue;
+use crate::error::GameError;
+use crate::tiles::{Tiles, TileTypes, GAME_STATE_TILESET};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// the map loads but probably isn't what was meant
+    Warning,
+    /// the map can't be played
+    Error,
+}
+
+/// Something wrong with a map.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Problem {
+    /// the file couldn't be read as a tiled map
+    Load(String),
+    NoGameState,
+    UnknownTile { x: usize, y: usize, id: u32 },
+    /// a layer whose size doesn't match the map's
+    LayerSize { layer: String, width: usize, height: usize, tiles: usize },
+    MissingTileset(String),
+    /// a game_state tile whose `type` isn't a known tile
+    UnknownTileType { id: u32, name: String },
+    /// the map's shop properties couldn't be used
+    Shop(String),
+    /// a tileset whose image can't be found
+    MissingImage { tileset: String, path: String },
+    MissingDoor,
+    NoCarrots,
+    /// no path of tiles from the door to a carrot
+    UnreachableDoor { x: usize, y: usize },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub problem: Problem,
+}
+
+impl Problem {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Problem::MissingImage { .. } | Problem::UnreachableDoor { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::Load(e) => write!(f, "could not load map: {}", e),
+            Problem::NoGameState => write!(f, "no layer has the GameState property set"),
+            Problem::UnknownTile { x, y, id } => write!(
+                f, "unknown tile id {} at ({}, {})", id, x, y),
+            Problem::LayerSize { layer, width, height, tiles } => write!(
+                f, "layer \"{}\" is {}x{} with {} tiles, which doesn't match the map",
+                layer, width, height, tiles),
+            Problem::MissingTileset(name) => write!(f, "no tileset named \"{}\"", name),
+            Problem::UnknownTileType { id, name } => write!(
+                f, "tile {} of the {} tileset has unknown type \"{}\"", id, GAME_STATE_TILESET, name),
+            Problem::Shop(e) => write!(f, "{}", e),
+            Problem::MissingImage { tileset, path } => write!(
+                f, "image \"{}\" of tileset \"{}\" not found", path, tileset),
+            Problem::MissingDoor => write!(f, "no door on the GameState layer"),
+            Problem::NoCarrots => write!(f, "no carrots on the GameState layer"),
+            Problem::UnreachableDoor { x, y } => write!(
+                f, "door at ({}, {}) is not connected to any carrot", x, y),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning: {}", self.problem),
+            Severity::Error => write!(f, "error: {}", self.problem),
+        }
+    }
+}
+
+impl From<Problem> for Diagnostic {
+    fn from(problem: Problem) -> Self {
+        Diagnostic { severity: problem.severity(), problem }
+    }
+}
+
+/// Load the map at `path` and check it, an empty list means the map is fine.
+pub fn validate(path: &Path) -> Vec<Diagnostic> {
+    let map = match tiled::Map::new(path) {
+        Ok(m) => m,
+        Err(e) => return vec![Problem::Load(format!("{:?}", e)).into()],
+    };
+    let mut diagnostics = Vec::new();
+    let types = match TileTypes::load(path) {
+        Ok(t) => t,
+        Err(e) => {
+            match e {
+                // reported by validate_map
+                GameError::MissingTileset(_) => (),
+                GameError::UnknownTileType { id, name } =>
+                    diagnostics.push(Problem::UnknownTileType { id, name }.into()),
+                e => diagnostics.push(Problem::Load(e.to_string()).into()),
+            }
+            TileTypes::default()
+        }
+    };
+    diagnostics.extend(validate_map(&map, &types, path.parent().unwrap_or(Path::new(""))));
+    diagnostics
+}
+
+/// Check a loaded map, `dir` is the folder the map was loaded from.
+pub fn validate_map(map: &tiled::Map, types: &TileTypes, dir: &Path) -> Vec<Diagnostic> {
+    let mut problems = Vec::new();
+    let (w, h) = (map.width as usize, map.height as usize);
+
+    if let Err(e) = Catalogue::default().for_map(&map.props, dir) {
+        problems.push(Problem::Shop(e.to_string()));
+    }
+    if !map.tilesets.iter().any(|t| t.name == GAME_STATE_TILESET) {
+        problems.push(Problem::MissingTileset(String::from(GAME_STATE_TILESET)));
+    }
+    for t in map.tilesets.iter() {
+        let image = Path::new(&t.image_path);
+        if !t.image_path.is_empty() && !image.exists() && !dir.join(image).exists() {
+            problems.push(Problem::MissingImage {
+                tileset: t.name.clone(), path: t.image_path.clone() });
+        }
+    }
+
+    let mut state = None;
+    for layer in map.layers.iter() {
+        let (lw, lh) = (layer.width as usize, layer.height as usize);
+        let sized = lw == w && lh == h && layer.tiles.len() == w * h;
+        if !sized {
+            problems.push(Problem::LayerSize {
+                layer: layer.info.name.clone(), width: lw, height: lh, tiles: layer.tiles.len() });
+        }
+        if sized && layer.props.booleans.get("GameState").copied().unwrap_or(false) {
+            state = Some(layer);
+        }
+    }
+    let layer = match state {
+        Some(l) => l,
+        None => {
+            // a GameState layer with the wrong size was already reported
+            if !problems.iter().any(|p| matches!(p, Problem::LayerSize { .. })) {
+                problems.push(Problem::NoGameState);
+            }
+            return problems.into_iter().map(Diagnostic::from).collect();
+        }
+    };
+
+    let mut tiles = vec![Tiles::None; w * h];
+    for (i, id) in layer.tiles.iter().enumerate() {
+        match types.get(*id) {
+            Some(t) => tiles[i] = t,
+            None => problems.push(Problem::UnknownTile { x: i % w, y: i / w, id: *id }),
+        }
+    }
+    let doors: Vec<usize> = (0..tiles.len()).filter(|i| tiles[*i] == Tiles::Door).collect();
+    if doors.is_empty() {
+        problems.push(Problem::MissingDoor);
+    }
+    if !tiles.contains(&Tiles::Carrot) {
+        problems.push(Problem::NoCarrots);
+    } else {
+        for d in doors {
+            if !reaches_carrot(&tiles, w, h, d) {
+                problems.push(Problem::UnreachableDoor { x: d % w, y: d / w });
+            }
+        }
+    }
+    problems.into_iter().map(Diagnostic::from).collect()
+}
+
+/// True if a path of game tiles, moving up, down, left or right, leads from `start` to a carrot.
+fn reaches_carrot(tiles: &[Tiles], w: usize, h: usize, start: usize) -> bool {
+    let mut seen = vec![false; tiles.len()];
+    let mut queue = VecDeque::from([start]);
+    seen[start] = true;
+    while let Some(i) = queue.pop_front() {
+        if tiles[i] == Tiles::Carrot {
+            return true;
+        }
+        let (x, y) = (i % w, i / w);
+        let mut next = Vec::with_capacity(4);
+        if x > 0 { next.push(i - 1); }
+        if x + 1 < w { next.push(i + 1); }
+        if y > 0 { next.push(i - w); }
+        if y + 1 < h { next.push(i + w); }
+        for n in next {
+            if !seen[n] && tiles[n] != Tiles::None {
+                seen[n] = true;
+                queue.push_back(n);
+            }
+        }
+    }
+    false
+}